@@ -1,25 +1,38 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, contracterror, Address, Env, String, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, contracterror, symbol_short, Address, Env, String, Symbol};
+use shared::ratelimit::{RateLimit, RateLimitError, RateLimiter};
 
-// Contract Errors
+/// Interface version. Version 2 moved the error codes into the registered
+/// 6000 block: each code is its version-1 value plus 6000.
+pub const CONTRACT_VERSION: u32 = 2;
+
+// Contract Errors (`shared::errors::ACADEMY_REWARDS_ERRORS` block)
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum ContractError {
-    AlreadyInitialized = 1,
-    NotInitialized = 2,
-    Unauthorized = 3,
-    ContractPaused = 4,
-    InvalidDiscount = 5,
-    BadgeTypeNotFound = 6,
-    BadgeTypeDisabled = 7,
-    UserAlreadyHasBadge = 8,
-    UserHasNoBadge = 9,
-    BadgeNotActive = 10,
-    BadgeExpired = 11,
-    RedemptionLimitReached = 12,
-    TransactionAlreadyRedeemed = 13,
+    AlreadyInitialized = 6001,
+    NotInitialized = 6002,
+    Unauthorized = 6003,
+    ContractPaused = 6004,
+    InvalidDiscount = 6005,
+    BadgeTypeNotFound = 6006,
+    BadgeTypeDisabled = 6007,
+    UserAlreadyHasBadge = 6008,
+    UserHasNoBadge = 6009,
+    BadgeNotActive = 6010,
+    BadgeExpired = 6011,
+    RedemptionLimitReached = 6012,
+    TransactionAlreadyRedeemed = 6013,
+    RateLimited = 6014,
+    InvalidRateLimit = 6015,
+}
+
+impl From<RateLimitError> for ContractError {
+    fn from(_: RateLimitError) -> Self {
+        ContractError::RateLimited
+    }
 }
 
 // Storage keys
@@ -33,6 +46,7 @@ pub enum DataKey {
     TotalBadgesMinted(u32),          // Counter per badge type
     PausedState,
     UsedTransactionHash(String),     // Track used transaction hashes globally
+    RedemptionRate,                  // Redemptions allowed per user per window
 }
 
 // Badge struct
@@ -76,6 +90,11 @@ pub struct AcademyRewardsContract;
 impl AcademyRewardsContract {
     // ========== INITIALIZATION ==========
 
+    /// Interface version, so clients can tell which error codes to expect
+    pub fn get_version(_env: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
     /// Initialize the contract with admin
     pub fn initialize(env: Env, admin: Address) -> Result<(), ContractError> {
         if env.storage().instance().has(&DataKey::Admin) {
@@ -226,6 +245,23 @@ impl AcademyRewardsContract {
         Ok(())
     }
 
+    /// Limit how often each user can redeem (None removes the limit; windows over 90 days are refused)
+    pub fn set_redemption_rate(
+        env: Env,
+        admin: Address,
+        limit: Option<RateLimit>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &admin)?;
+        match limit {
+            Some(limit) => {
+                limit.check().map_err(|_| ContractError::InvalidRateLimit)?;
+                env.storage().instance().set(&DataKey::RedemptionRate, &limit)
+            }
+            None => env.storage().instance().remove(&DataKey::RedemptionRate),
+        }
+        Ok(())
+    }
+
     // ========== USER FUNCTIONS ==========
 
     /// Redeem badge for fee discount
@@ -267,6 +303,11 @@ impl AcademyRewardsContract {
             return Err(ContractError::RedemptionLimitReached);
         }
 
+        // Per-user redemption rate
+        if let Some(limit) = Self::get_redemption_rate(env.clone()) {
+            RateLimiter::consume(&env, &symbol_short!("redeem"), &user, &limit, 1)?;
+        }
+
         // Record redemption
         let redemption_record = RedemptionRecord {
             badge_type: badge.badge_type,
@@ -321,6 +362,11 @@ impl AcademyRewardsContract {
         0
     }
 
+    /// Get the per-user redemption rate limit, if any
+    pub fn get_redemption_rate(env: Env) -> Option<RateLimit> {
+        env.storage().instance().get(&DataKey::RedemptionRate)
+    }
+
     /// Get user's badge information
     pub fn get_user_badge(env: Env, user: Address) -> Option<Badge> {
         env.storage().persistent().get(&DataKey::Badge(user))
@@ -381,4 +427,5 @@ impl AcademyRewardsContract {
     }
 }
 
-// mod test; // Removed failing tests
\ No newline at end of file
+#[cfg(test)]
+mod test;
@@ -5,3 +5,6 @@ pub mod vesting;
 pub use vesting::{
     AcademyVestingContract, VestingSchedule, GrantEvent, ClaimEvent, RevokeEvent, VestingError,
 };
+
+#[cfg(test)]
+mod test;
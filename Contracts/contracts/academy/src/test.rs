@@ -1,820 +1,370 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use soroban_sdk::{testutils::*, Address, Env};
-
-    fn create_test_env() -> (Env, Address, Address, Address) {
-        let env = Env::default();
-        let admin = Address::random(&env);
-        let reward_token = Address::random(&env);
-        let governance = Address::random(&env);
-
-        (env, admin, reward_token, governance)
-    }
+#![cfg(test)]
 
-    #[test]
-    fn test_contract_initialization() {
-        let (env, admin, reward_token, governance) = create_test_env();
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            reward_token.clone(),
-            governance.clone(),
-        )
-        .expect("Failed to initialize");
-
-        // Verify info is stored correctly
-        let (stored_admin, stored_token, stored_gov) =
-            AcademyVestingContract::get_info(env).expect("Failed to get info");
-
-        assert_eq!(stored_admin, admin);
-        assert_eq!(stored_token, reward_token);
-        assert_eq!(stored_gov, governance);
-    }
+use crate::vesting::{AcademyVestingContract, AcademyVestingContractClient, VestingError};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
 
-    #[test]
-    fn test_contract_cannot_be_initialized_twice() {
-        let (env, admin, reward_token, governance) = create_test_env();
-
-        // First initialization should succeed
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            reward_token.clone(),
-            governance.clone(),
-        )
-        .expect("First init failed");
-
-        // Second initialization should fail
-        let result = AcademyVestingContract::init(
-            env,
-            admin,
-            reward_token,
-            governance,
-        );
+fn setup(env: &Env) -> (AcademyVestingContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Unauthorized);
-    }
+    let admin = Address::generate(env);
+    let governance = Address::generate(env);
+    let reward_token = env.register_stellar_asset_contract(admin.clone());
 
-    #[test]
-    fn test_grant_vesting_schedule() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        // Grant vesting schedule
-        let grant_id = AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary.clone(),
-            1000,
-            100,   // start_time
-            60,    // cliff (60 seconds)
-            3600,  // duration (1 hour total)
-        )
-        .expect("Grant failed");
-
-        assert_eq!(grant_id, 1);
-
-        // Retrieve and verify schedule
-        let schedule = AcademyVestingContract::get_vesting(env, grant_id)
-            .expect("Get vesting failed");
-
-        assert_eq!(schedule.beneficiary, beneficiary);
-        assert_eq!(schedule.amount, 1000);
-        assert_eq!(schedule.start_time, 100);
-        assert_eq!(schedule.cliff, 60);
-        assert_eq!(schedule.duration, 3600);
-        assert!(!schedule.claimed);
-        assert!(!schedule.revoked);
-    }
+    let contract_id = env.register_contract(None, AcademyVestingContract);
+    let client = AcademyVestingContractClient::new(env, &contract_id);
+    client.init(&admin, &reward_token, &governance);
 
-    #[test]
-    fn test_grant_multiple_schedules() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary1 = Address::random(&env);
-        let beneficiary2 = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        // Grant first schedule
-        let grant_id1 = AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary1,
-            1000,
-            100,
-            60,
-            3600,
-        )
-        .expect("Grant 1 failed");
-
-        // Grant second schedule
-        let grant_id2 = AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary2,
-            2000,
-            200,
-            120,
-            7200,
-        )
-        .expect("Grant 2 failed");
-
-        // IDs should be sequential
-        assert_eq!(grant_id1, 1);
-        assert_eq!(grant_id2, 2);
-    }
+    (client, admin, reward_token, governance)
+}
 
-    #[test]
-    fn test_grant_with_invalid_schedule() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        // Test: negative amount
-        let result = AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary.clone(),
-            -1000,
-            100,
-            60,
-            3600,
-        );
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::InvalidSchedule);
-
-        // Test: cliff > duration
-        let result = AcademyVestingContract::grant_vesting(
-            env,
-            admin,
-            beneficiary,
-            1000,
-            100,
-            5000, // cliff > duration
-            3600,
-        );
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::InvalidSchedule);
-    }
+/// Fund the vesting contract with reward tokens
+fn fund(env: &Env, client: &AcademyVestingContractClient, reward_token: &Address, amount: i128) {
+    StellarAssetClient::new(env, reward_token).mint(&client.address, &amount);
+}
 
-    #[test]
-    fn test_non_admin_cannot_grant() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let non_admin = Address::random(&env);
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin,
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        // Non-admin tries to grant
-        let result = AcademyVestingContract::grant_vesting(
-            env,
-            non_admin,
-            beneficiary,
-            1000,
-            100,
-            60,
-            3600,
-        );
+fn set_time(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|li| li.timestamp = timestamp);
+}
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Unauthorized);
-    }
+#[test]
+fn test_contract_initialization() {
+    let env = Env::default();
+    let (client, admin, reward_token, governance) = setup(&env);
 
-    #[test]
-    fn test_vesting_calculation_before_start() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            1000,
-            start_time,
-            300,  // cliff
-            3600, // duration
-        )
-        .expect("Grant failed");
-
-        // Mock ledger time to before start
-        env.ledger().set_timestamp(start_time - 100);
-
-        let vested = AcademyVestingContract::get_vested_amount(env, 1)
-            .expect("Get vested failed");
-
-        assert_eq!(vested, 0); // Nothing vested yet
-    }
+    assert_eq!(client.get_info(), (admin, reward_token, governance));
+}
 
-    #[test]
-    fn test_vesting_calculation_before_cliff() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-        let cliff = 300u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            1000,
-            start_time,
-            cliff,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Mock ledger time to after start but before cliff
-        env.ledger().set_timestamp(start_time + cliff - 50);
-
-        let vested = AcademyVestingContract::get_vested_amount(env, 1)
-            .expect("Get vested failed");
-
-        assert_eq!(vested, 0); // Still nothing vested (cliff not passed)
-    }
+#[test]
+fn test_contract_cannot_be_initialized_twice() {
+    let env = Env::default();
+    let (client, admin, reward_token, governance) = setup(&env);
 
-    #[test]
-    fn test_vesting_calculation_after_cliff() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-        let cliff = 300u64;
-        let duration = 3600u64;
-        let amount = 1000i128;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            amount,
-            start_time,
-            cliff,
-            duration,
-        )
-        .expect("Grant failed");
-
-        // Mock ledger time to exactly at cliff
-        env.ledger().set_timestamp(start_time + cliff);
-
-        let vested = AcademyVestingContract::get_vested_amount(env, 1)
-            .expect("Get vested failed");
-
-        assert_eq!(vested, 0); // Vesting starts linearly after cliff
-    }
+    assert_eq!(
+        client.try_init(&admin, &reward_token, &governance),
+        Err(Ok(VestingError::Unauthorized.into()))
+    );
+}
 
-    #[test]
-    fn test_vesting_calculation_fully_vested() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-        let cliff = 300u64;
-        let duration = 3600u64;
-        let amount = 1000i128;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            amount,
-            start_time,
-            cliff,
-            duration,
-        )
-        .expect("Grant failed");
-
-        // Mock ledger time to after full duration
-        env.ledger().set_timestamp(start_time + duration + 1000);
-
-        let vested = AcademyVestingContract::get_vested_amount(env, 1)
-            .expect("Get vested failed");
-
-        assert_eq!(vested, amount); // Fully vested
-    }
+#[test]
+fn test_grant_vesting_schedule() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+
+    // 60 second cliff, 1 hour total
+    let grant_id = client.grant_vesting(&admin, &beneficiary, &1000, &100, &60, &3600);
+    assert_eq!(grant_id, 1);
+
+    let schedule = client.get_vesting(&grant_id);
+    assert_eq!(schedule.beneficiary, beneficiary);
+    assert_eq!(schedule.amount, 1000);
+    assert_eq!(schedule.start_time, 100);
+    assert_eq!(schedule.cliff, 60);
+    assert_eq!(schedule.duration, 3600);
+    assert!(!schedule.claimed);
+    assert!(!schedule.revoked);
+}
 
-    #[test]
-    fn test_vesting_calculation_partial() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 0u64;
-        let cliff = 100u64;
-        let duration = 1000u64;
-        let amount = 1000i128;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            amount,
-            start_time,
-            cliff,
-            duration,
-        )
-        .expect("Grant failed");
-
-        // Mock ledger time to 50% through vesting (after cliff)
-        // Time: cliff + (duration - cliff) / 2 = 100 + 450 = 550
-        env.ledger().set_timestamp(start_time + cliff + 450);
-
-        let vested = AcademyVestingContract::get_vested_amount(env, 1)
-            .expect("Get vested failed");
-
-        // Should be approximately 50% of amount (500)
-        assert!(vested >= 490 && vested <= 510); // Allow small rounding
-    }
+#[test]
+fn test_grant_multiple_schedules() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
 
-    #[test]
-    fn test_claim_not_vested() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary.clone(),
-            1000,
-            start_time,
-            300,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Try to claim before vesting
-        env.ledger().set_timestamp(start_time - 100);
-
-        let result = AcademyVestingContract::claim(env, 1, beneficiary);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::NotVested);
-    }
+    let grant_id1 = client.grant_vesting(&admin, &Address::generate(&env), &1000, &100, &60, &3600);
+    let grant_id2 = client.grant_vesting(&admin, &Address::generate(&env), &2000, &200, &120, &7200);
 
-    #[test]
-    fn test_claim_single_semantics_prevents_double_claim() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 0u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary.clone(),
-            1000,
-            start_time,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Set time to after cliff
-        env.ledger().set_timestamp(start_time + 200);
-
-        // First claim should fail because insufficient balance (mock issue)
-        // In real scenario with token setup, first claim would succeed
-        // Second claim would fail with AlreadyClaimed
-
-        // For this test, we verify the logic by checking schedule state
-        let schedule = AcademyVestingContract::get_vesting(env.clone(), 1)
-            .expect("Get vesting failed");
-        assert!(!schedule.claimed);
-
-        // Simulate the claimed state by attempting second claim
-        // (In real test with token, first claim would mark it as claimed)
-    }
+    // IDs are sequential
+    assert_eq!(grant_id1, 1);
+    assert_eq!(grant_id2, 2);
+}
 
-    #[test]
-    fn test_claim_revoked_schedule() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 0u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary.clone(),
-            1000,
-            start_time,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Revoke the schedule
-        env.ledger().set_timestamp(start_time + 3600); // After start + revoke_delay
-        AcademyVestingContract::revoke(env.clone(), 1, admin.clone(), 3600)
-            .expect("Revoke failed");
-
-        // Try to claim revoked schedule
-        let result = AcademyVestingContract::claim(env, 1, beneficiary);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Revoked);
-    }
+#[test]
+fn test_grant_with_invalid_schedule() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+
+    // Negative amount
+    assert_eq!(
+        client.try_grant_vesting(&admin, &beneficiary, &-1000, &100, &60, &3600),
+        Err(Ok(VestingError::InvalidSchedule))
+    );
+
+    // Cliff longer than the duration
+    assert_eq!(
+        client.try_grant_vesting(&admin, &beneficiary, &1000, &100, &5000, &3600),
+        Err(Ok(VestingError::InvalidSchedule))
+    );
+}
 
-    #[test]
-    fn test_revoke_invalid_timelock() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary,
-            1000,
-            0,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Try to revoke with insufficient timelock (< 1 hour)
-        let result = AcademyVestingContract::revoke(env, 1, admin, 100); // 100 seconds < 3600
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::InvalidTimelock);
-    }
+#[test]
+fn test_non_admin_cannot_grant() {
+    let env = Env::default();
+    let (client, _admin, _reward_token, _governance) = setup(&env);
+    let non_admin = Address::generate(&env);
 
-    #[test]
-    fn test_revoke_not_enough_time_elapsed() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 1000u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary,
-            1000,
-            start_time,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Try to revoke too early (before revoke_delay elapsed)
-        env.ledger().set_timestamp(start_time + 1000); // Only 1000 seconds elapsed
-
-        let result = AcademyVestingContract::revoke(env, 1, admin, 3600); // 3600 second delay
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::NotEnoughTimeForRevoke);
-    }
+    assert_eq!(
+        client.try_grant_vesting(&non_admin, &Address::generate(&env), &1000, &100, &60, &3600),
+        Err(Ok(VestingError::Unauthorized))
+    );
+}
 
-    #[test]
-    fn test_revoke_cannot_revoke_claimed() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary.clone(),
-            1000,
-            0,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Cannot actually test claim without token setup, but we test the revoke constraint
-        // by checking that revoke fails when trying to revoke a schedule
+#[test]
+fn test_vesting_calculation_over_the_schedule() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let grant_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &1000, &100, &1000);
+
+    // Nothing before the start or the cliff, and vesting only starts once the cliff passes
+    for timestamp in [900, 1050, 1100] {
+        set_time(&env, timestamp);
+        assert_eq!(client.get_vested_amount(&grant_id), 0);
     }
 
-    #[test]
-    fn test_revoke_cannot_revoke_twice() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        let start_time = 0u64;
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary,
-            1000,
-            start_time,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        // Set time to allow revocation
-        env.ledger().set_timestamp(start_time + 3600);
-
-        // First revoke should succeed
-        AcademyVestingContract::revoke(env.clone(), 1, admin.clone(), 3600)
-            .expect("First revoke failed");
-
-        // Second revoke should fail
-        let result = AcademyVestingContract::revoke(env, 1, admin, 3600);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Revoked);
+    // Linear after the cliff: halfway through the remaining 900 seconds
+    set_time(&env, 1100 + 450);
+    assert_eq!(client.get_vested_amount(&grant_id), 500);
+
+    set_time(&env, 2000 + 1000);
+    assert_eq!(client.get_vested_amount(&grant_id), 1000);
+}
+
+#[test]
+fn test_claim_not_vested() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let grant_id = client.grant_vesting(&admin, &beneficiary, &1000, &1000, &300, &3600);
+
+    set_time(&env, 900);
+    assert_eq!(client.try_claim(&grant_id, &beneficiary), Err(Ok(VestingError::NotVested)));
+}
+
+#[test]
+fn test_claim_pays_once() {
+    let env = Env::default();
+    let (client, admin, reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let grant_id = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+
+    // Underfunded contracts refuse the claim without marking it
+    set_time(&env, 3600);
+    assert_eq!(client.try_claim(&grant_id, &beneficiary), Err(Ok(VestingError::InsufficientBalance)));
+    assert!(!client.get_vesting(&grant_id).claimed);
+
+    fund(&env, &client, &reward_token, 1000);
+    assert_eq!(client.claim(&grant_id, &beneficiary), 1000);
+    assert_eq!(TokenClient::new(&env, &reward_token).balance(&beneficiary), 1000);
+    assert!(client.get_vesting(&grant_id).claimed);
+
+    assert_eq!(client.try_claim(&grant_id, &beneficiary), Err(Ok(VestingError::AlreadyClaimed)));
+}
+
+#[test]
+fn test_claim_wrong_beneficiary() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let grant_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &0, &100, &3600);
+
+    set_time(&env, 200);
+    assert_eq!(
+        client.try_claim(&grant_id, &Address::generate(&env)),
+        Err(Ok(VestingError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_claim_revoked_schedule() {
+    let env = Env::default();
+    let (client, admin, reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let grant_id = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    fund(&env, &client, &reward_token, 1000);
+
+    set_time(&env, 3600);
+    client.revoke(&grant_id, &admin, &3600);
+    assert_eq!(client.try_claim(&grant_id, &beneficiary), Err(Ok(VestingError::Revoked)));
+}
+
+#[test]
+fn test_revoke_timelock() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let grant_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &1000, &100, &3600);
+
+    // Below the 1 hour floor
+    assert_eq!(client.try_revoke(&grant_id, &admin, &100), Err(Ok(VestingError::InvalidTimelock)));
+
+    // The delay runs from the schedule start
+    set_time(&env, 2000);
+    assert_eq!(
+        client.try_revoke(&grant_id, &admin, &3600),
+        Err(Ok(VestingError::NotEnoughTimeForRevoke))
+    );
+}
+
+#[test]
+fn test_revoke_only_once_and_not_after_claim() {
+    let env = Env::default();
+    let (client, admin, reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let revoked_id = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    let claimed_id = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    fund(&env, &client, &reward_token, 1000);
+
+    set_time(&env, 3600);
+    client.revoke(&revoked_id, &admin, &3600);
+    assert_eq!(client.try_revoke(&revoked_id, &admin, &3600), Err(Ok(VestingError::Revoked)));
+
+    client.claim(&claimed_id, &beneficiary);
+    assert_eq!(client.try_revoke(&claimed_id, &admin, &3600), Err(Ok(VestingError::AlreadyClaimed)));
+}
+
+#[test]
+fn test_non_admin_cannot_revoke() {
+    let env = Env::default();
+    let (client, admin, _reward_token, _governance) = setup(&env);
+    let grant_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &0, &100, &3600);
+
+    set_time(&env, 3600);
+    assert_eq!(
+        client.try_revoke(&grant_id, &Address::generate(&env), &3600),
+        Err(Ok(VestingError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_unknown_grants() {
+    let env = Env::default();
+    let (client, _admin, _reward_token, _governance) = setup(&env);
+
+    assert_eq!(client.try_get_vesting(&999).err(), Some(Ok(VestingError::GrantNotFound)));
+    assert_eq!(client.try_get_vested_amount(&999), Err(Ok(VestingError::GrantNotFound)));
+}
+
+/// Reward token that tries to claim another grant from inside `transfer()`
+#[contract]
+pub struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn set_target(env: Env, target: Address, grant_id: u64) {
+        env.storage().instance().set(&symbol_short!("target"), &target);
+        env.storage().instance().set(&symbol_short!("grant"), &grant_id);
     }
 
-    #[test]
-    fn test_non_admin_cannot_revoke() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let non_admin = Address::random(&env);
-        let beneficiary = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin,
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary,
-            1000,
-            0,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        env.ledger().set_timestamp(3600);
-
-        // Non-admin tries to revoke
-        let result = AcademyVestingContract::revoke(env, 1, non_admin, 3600);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Unauthorized);
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        i128::MAX
     }
 
-    #[test]
-    fn test_claim_wrong_beneficiary() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-        let other = Address::random(&env);
-
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin,
-            beneficiary,
-            1000,
-            0,
-            100,
-            3600,
-        )
-        .expect("Grant failed");
-
-        env.ledger().set_timestamp(200);
-
-        // Different beneficiary tries to claim
-        let result = AcademyVestingContract::claim(env, 1, other);
-
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::Unauthorized);
+    pub fn transfer(env: Env, _from: Address, to: Address, _amount: i128) {
+        let target: Address = env.storage().instance().get(&symbol_short!("target")).unwrap();
+        let grant_id: u64 = env.storage().instance().get(&symbol_short!("grant")).unwrap();
+        let reentered = AcademyVestingContractClient::new(&env, &target).try_claim(&grant_id, &to);
+        env.storage().instance().set(&symbol_short!("reentered"), &reentered.is_ok());
     }
 
-    #[test]
-    fn test_get_vesting_nonexistent() {
-        let (env, admin, _reward_token, governance) = create_test_env();
+    pub fn reentered(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("reentered")).unwrap_or(false)
+    }
+}
 
-        AcademyVestingContract::init(
-            env.clone(),
-            admin,
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
+#[test]
+fn test_malicious_token_cannot_reenter_claim() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
 
-        // Try to get nonexistent grant
-        let result = AcademyVestingContract::get_vesting(env, 999);
+    let admin = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let token_id = env.register_contract(None, ReentrantToken);
+    let token = ReentrantTokenClient::new(&env, &token_id);
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::GrantNotFound);
-    }
+    let contract_id = env.register_contract(None, AcademyVestingContract);
+    let client = AcademyVestingContractClient::new(&env, &contract_id);
+    client.init(&admin, &token_id, &Address::generate(&env));
 
-    #[test]
-    fn test_get_vested_amount_nonexistent() {
-        let (env, admin, _reward_token, governance) = create_test_env();
+    let first = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    let second = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    token.set_target(&contract_id, &second);
 
-        AcademyVestingContract::init(
-            env.clone(),
-            admin,
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
+    set_time(&env, 3600);
+    assert_eq!(client.claim(&first, &beneficiary), 1000);
 
-        // Try to get vested amount for nonexistent grant
-        let result = AcademyVestingContract::get_vested_amount(env, 999);
+    // The nested claim of the second grant from inside transfer() was refused
+    assert!(!token.reentered());
+    assert!(client.get_vesting(&first).claimed);
+    assert!(!client.get_vesting(&second).claimed);
+}
 
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), VestingError::GrantNotFound);
-    }
+#[test]
+fn test_held_claim_lock_refuses_with_guard_error() {
+    use shared::guard::ReentrancyGuard;
+
+    let env = Env::default();
+    let (client, admin, reward_token, _governance) = setup(&env);
+    let beneficiary = Address::generate(&env);
+    let grant_id = client.grant_vesting(&admin, &beneficiary, &1000, &0, &100, &3600);
+    fund(&env, &client, &reward_token, 1000);
+    set_time(&env, 3600);
+
+    // A lock left held, as it is for the duration of an outer claim's
+    // transfer, refuses the entry point with the guard's error
+    env.as_contract(&client.address, || {
+        core::mem::forget(ReentrancyGuard::enter(&env, symbol_short!("claim")).unwrap());
+    });
+    assert_eq!(client.try_claim(&grant_id, &beneficiary), Err(Ok(VestingError::Reentered)));
+    assert!(!client.get_vesting(&grant_id).claimed);
+
+    env.as_contract(&client.address, || {
+        env.storage().instance().remove(&(symbol_short!("lock"), symbol_short!("claim")));
+    });
+    assert_eq!(client.claim(&grant_id, &beneficiary), 1000);
+}
 
-    #[test]
-    fn test_integration_complete_vesting_flow() {
-        let (env, admin, _reward_token, governance) = create_test_env();
-        let beneficiary = Address::random(&env);
-
-        // Initialize contract
-        AcademyVestingContract::init(
-            env.clone(),
-            admin.clone(),
-            Address::random(&env),
-            governance,
-        )
-        .expect("Init failed");
-
-        // Backend grants vesting schedule
-        let start_time = 0u64;
-        let cliff = 1000u64;
-        let duration = 10000u64;
-        let amount = 5000i128;
-
-        let grant_id = AcademyVestingContract::grant_vesting(
-            env.clone(),
-            admin.clone(),
-            beneficiary.clone(),
-            amount,
-            start_time,
-            cliff,
-            duration,
-        )
-        .expect("Grant failed");
-
-        // Check vesting status before cliff
-        env.ledger().set_timestamp(start_time + 500);
-        let vested_before = AcademyVestingContract::get_vested_amount(env.clone(), grant_id)
-            .expect("Get vested before cliff failed");
-        assert_eq!(vested_before, 0);
-
-        // Check vesting status at cliff
-        env.ledger().set_timestamp(start_time + cliff);
-        let vested_at_cliff = AcademyVestingContract::get_vested_amount(env.clone(), grant_id)
-            .expect("Get vested at cliff failed");
-        assert_eq!(vested_at_cliff, 0); // Vesting starts linearly after cliff
-
-        // Check vesting status midway
-        env.ledger().set_timestamp(start_time + cliff + (duration - cliff) / 2);
-        let vested_midway = AcademyVestingContract::get_vested_amount(env.clone(), grant_id)
-            .expect("Get vested midway failed");
-        assert!(vested_midway > 0 && vested_midway < amount); // Partially vested
-
-        // Check vesting status after full duration
-        env.ledger().set_timestamp(start_time + duration + 1000);
-        let vested_full = AcademyVestingContract::get_vested_amount(env.clone(), grant_id)
-            .expect("Get vested full failed");
-        assert_eq!(vested_full, amount); // Fully vested
-
-        // Verify schedule details
-        let schedule = AcademyVestingContract::get_vesting(env, grant_id)
-            .expect("Get vesting failed");
-        assert_eq!(schedule.beneficiary, beneficiary);
-        assert_eq!(schedule.amount, amount);
-        assert_eq!(schedule.cliff, cliff);
-        assert_eq!(schedule.duration, duration);
-        assert!(!schedule.claimed);
-        assert!(!schedule.revoked);
+#[test]
+fn test_revoke_delay_floor_is_bounded_and_changes_after_delay() {
+    use crate::vesting::{DEFAULT_REVOKE_DELAY, REVOKE_DELAY_PARAM};
+    use shared::governance::GovernanceError;
+
+    let env = Env::default();
+    let (client, admin, _reward_token, governance) = setup(&env);
+    let grant_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &0, &100, &3600);
+    set_time(&env, 10_000);
+    assert_eq!(client.get_param(&REVOKE_DELAY_PARAM), Some(DEFAULT_REVOKE_DELAY as i128));
+
+    // 1 hour to 30 days; unknown keys are refused
+    for value in [3_599, 2_592_001] {
+        assert_eq!(
+            client.try_set_param(&REVOKE_DELAY_PARAM, &value),
+            Err(Ok(GovernanceError::ParamOutOfBounds))
+        );
     }
+    assert_eq!(
+        client.try_set_param(&symbol_short!("nope"), &7_200),
+        Err(Ok(GovernanceError::UnknownParam))
+    );
+
+    // Only governance schedules changes, and they wait out the 2 day delay
+    assert_eq!(client.set_param(&REVOKE_DELAY_PARAM, &7_200), 10_000 + 172_800);
+    assert_eq!(env.auths()[0].0, governance);
+    assert_eq!(
+        client.try_set_param(&REVOKE_DELAY_PARAM, &10_800),
+        Err(Ok(GovernanceError::ParamChangePending))
+    );
+    assert_eq!(client.get_param(&REVOKE_DELAY_PARAM), Some(3_600));
+    assert_eq!(client.get_param_history(&REVOKE_DELAY_PARAM).len(), 1);
+
+    // The old floor holds until then
+    let pending_id = client.grant_vesting(&admin, &Address::generate(&env), &1000, &0, &100, &3600);
+    client.revoke(&pending_id, &admin, &3_600);
+
+    set_time(&env, 10_000 + 172_800);
+    assert_eq!(client.get_param(&REVOKE_DELAY_PARAM), Some(7_200));
+    assert_eq!(client.try_revoke(&grant_id, &admin, &3_600), Err(Ok(VestingError::InvalidTimelock)));
+    client.revoke(&grant_id, &admin, &7_200);
 }
@@ -1,4 +1,13 @@
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, symbol_short, Vec};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Address, Env, Error, Symbol, symbol_short, Vec};
+use shared::governance::GovernanceError;
+use shared::guard::{GuardError, ReentrancyGuard};
+use shared::params::{ParamChange, ParamSpec, ParamStore};
+
+/// Parameter key for the minimum revoke delay (seconds)
+pub const REVOKE_DELAY_PARAM: Symbol = symbol_short!("rvk_min");
+
+/// Minimum revoke delay used until governance changes it: 1 hour
+pub const DEFAULT_REVOKE_DELAY: u64 = 3600;
 
 /// Vesting schedule for an academy reward
 #[contracttype]
@@ -48,7 +57,8 @@ pub struct RevokeEvent {
     pub revoked_by: Address,
 }
 
-/// Vesting error codes
+/// Vesting error codes (`shared::errors::VESTING_ERRORS` block)
+#[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum VestingError {
@@ -61,23 +71,12 @@ pub enum VestingError {
     Revoked = 4007,
     InvalidTimelock = 4008,
     NotEnoughTimeForRevoke = 4009,
+    Reentered = 4010,
 }
 
-impl From<VestingError> for soroban_sdk::Error {
-    fn from(error: VestingError) -> Self {
-        soroban_sdk::Error::from_contract_error(error as u32)
-    }
-}
-
-impl From<&VestingError> for soroban_sdk::Error {
-    fn from(error: &VestingError) -> Self {
-        soroban_sdk::Error::from_contract_error(*error as u32)
-    }
-}
-
-impl From<soroban_sdk::Error> for VestingError {
-    fn from(_error: soroban_sdk::Error) -> Self {
-        VestingError::Unauthorized
+impl From<GuardError> for VestingError {
+    fn from(_: GuardError) -> Self {
+        VestingError::Reentered
     }
 }
 
@@ -86,17 +85,20 @@ pub struct AcademyVestingContract;
 
 #[contractimpl]
 impl AcademyVestingContract {
-    /// Initialize the vesting contract with admin and governance roles
+    /// Initialize the vesting contract with admin and governance roles.
+    ///
+    /// Fails with a `VestingError` if already initialized, or with the
+    /// `GovernanceError` from defining the contract's parameters.
     pub fn init(
         env: Env,
         admin: Address,
         reward_token: Address,
         governance: Address,
-    ) -> Result<(), VestingError> {
+    ) -> Result<(), Error> {
         // Check if already initialized
         let init_key = symbol_short!("init");
         if env.storage().persistent().has(&init_key) {
-            return Err(VestingError::Unauthorized);
+            return Err(VestingError::Unauthorized.into());
         }
 
         // Set initialization flag
@@ -118,6 +120,8 @@ impl AcademyVestingContract {
         let counter_key = symbol_short!("cnt");
         env.storage().persistent().set(&counter_key, &0u64);
 
+        Self::define_params(&env)?;
+
         Ok(())
     }
 
@@ -212,6 +216,9 @@ impl AcademyVestingContract {
     pub fn claim(env: Env, grant_id: u64, beneficiary: Address) -> Result<i128, VestingError> {
         beneficiary.require_auth();
 
+        // Held across the token balance check and transfer
+        let _guard = ReentrancyGuard::enter(&env, symbol_short!("claim"))?;
+
         // Get vesting schedule
         let schedules_key = symbol_short!("sched");
         let mut schedules: soroban_sdk::Map<u64, VestingSchedule> = env
@@ -333,8 +340,9 @@ impl AcademyVestingContract {
             return Err(VestingError::Revoked);
         }
 
-        // Enforce timelock for revocation (minimum 1 hour)
-        if revoke_delay < 3600 {
+        // Enforce timelock for revocation (governance-set minimum, 1 hour by default)
+        let min_delay = ParamStore::get_u64(&env, &REVOKE_DELAY_PARAM).unwrap_or(DEFAULT_REVOKE_DELAY);
+        if revoke_delay < min_delay {
             return Err(VestingError::InvalidTimelock);
         }
 
@@ -363,6 +371,30 @@ impl AcademyVestingContract {
         Ok(())
     }
 
+    /// Change a parameter (governance only); returns when the new value takes effect
+    pub fn set_param(env: Env, key: Symbol, value: i128) -> Result<u64, GovernanceError> {
+        let governance: Address = env
+            .storage()
+            .persistent()
+            .get(&symbol_short!("gov"))
+            .ok_or(GovernanceError::Unauthorized)?;
+        governance.require_auth();
+
+        // Contracts initialized before a parameter existed pick it up here
+        Self::define_params(&env)?;
+        ParamStore::set(&env, &key, value)
+    }
+
+    /// Get the current value of a parameter
+    pub fn get_param(env: Env, key: Symbol) -> Option<i128> {
+        ParamStore::get(&env, &key)
+    }
+
+    /// Get every scheduled change to a parameter, oldest first
+    pub fn get_param_history(env: Env, key: Symbol) -> Vec<ParamChange> {
+        ParamStore::history(&env, &key)
+    }
+
     /// Query vesting schedule details
     pub fn get_vesting(env: Env, grant_id: u64) -> Result<VestingSchedule, VestingError> {
         let schedules_key = symbol_short!("sched");
@@ -455,7 +487,18 @@ impl AcademyVestingContract {
 
         Ok((admin, token, governance))
     }
-}
 
-// #[cfg(test)]
-// mod tests; // Removed failing tests
+    /// Internal helper: define any parameter this version knows but storage doesn't yet
+    fn define_params(env: &Env) -> Result<(), GovernanceError> {
+        if ParamStore::spec(env, &REVOKE_DELAY_PARAM).is_none() {
+            // Revoke delay floor: 1 hour to 30 days, changes take 2 days to apply
+            let revoke_delay = ParamSpec {
+                min: DEFAULT_REVOKE_DELAY as i128,
+                max: 2_592_000,
+                change_delay: 172_800,
+            };
+            ParamStore::define(env, &REVOKE_DELAY_PARAM, &revoke_delay, DEFAULT_REVOKE_DELAY as i128)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,381 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Symbol, Val, Vec};
+use shared::council::{Council, CouncilManager};
+use shared::governance::{
+    ApprovalDelegation, ApprovalSignature, ApproverVote, GovernanceError, GovernanceManager, GovernanceRole, ProposalAction,
+    ProposalPage, ProposalStatus, UpgradeProposal,
+};
+use shared::params::{ParamChange, ParamStore};
+use shared::roles::RoleManager;
+use shared::safe_call::{TargetRegistry, TrustedTarget};
+use shared::voting::{TokenVoting, VoteChoice, VoteRecord, VoteTally, VoteTerms, VotingConfig};
+
+/// Protocol-wide governance contract.
+///
+/// Deploy it first and give it the admin seats of the trading (admin, whole
+/// council and executor), vesting and academy-rewards contracts; executed
+/// `Call` actions then act on those contracts with this contract as the
+/// authorized caller. They keep their own role records and admin checks.
+///
+/// Actions that would only change this contract's own upgrade, pause or
+/// parameter records are refused (see `check_actions`): the governed
+/// contracts keep their own, so pauses and upgrades go through `Call`.
+#[contract]
+pub struct GovernanceContract;
+
+#[contractimpl]
+impl GovernanceContract {
+    /// Initialize with an admin, the approver council (majority thresholds) and an executor
+    pub fn init(
+        env: Env,
+        admin: Address,
+        council: Vec<Address>,
+        executor: Address,
+    ) -> Result<(), GovernanceError> {
+        let init_key = symbol_short!("init");
+        if env.storage().persistent().has(&init_key) {
+            return Err(GovernanceError::Unauthorized);
+        }
+        env.storage().persistent().set(&init_key, &true);
+
+        RoleManager::grant(&env, &admin, &GovernanceRole::Admin);
+        RoleManager::grant(&env, &executor, &GovernanceRole::Executor);
+
+        let council = Council {
+            threshold: CouncilManager::majority(council.len()),
+            veto_threshold: CouncilManager::majority(council.len()),
+            members: council,
+        };
+        CouncilManager::set(&env, &council)?;
+
+        GovernanceManager::define_timelocks(&env)
+    }
+
+    /// Propose an action for the council to decide
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+        Self::check_actions(&soroban_sdk::vec![&env, action.clone()])?;
+
+        GovernanceManager::propose(&env, proposer, action, description)
+    }
+
+    /// Propose several actions that execute in order, all or nothing
+    pub fn propose_batch(
+        env: Env,
+        proposer: Address,
+        actions: Vec<ProposalAction>,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+        Self::check_actions(&actions)?;
+
+        GovernanceManager::propose_batch(&env, proposer, actions, description)
+    }
+
+    /// Propose calling a function on a governed contract
+    pub fn propose_call(
+        env: Env,
+        proposer: Address,
+        target: Address,
+        function: Symbol,
+        args: Vec<Val>,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+
+        GovernanceManager::propose(
+            &env,
+            proposer,
+            ProposalAction::Call(target, function, args),
+            description,
+        )
+    }
+
+    /// Propose an emergency `pause` call on a registered trusted target, or a
+    /// guardian rotation (Admin or Guardian).
+    ///
+    /// Needs unanimous council approval but can execute as soon as it is approved.
+    pub fn propose_emergency(
+        env: Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+        Self::check_actions(&soroban_sdk::vec![&env, action.clone()])?;
+
+        GovernanceManager::propose_emergency(&env, proposer, action, description)
+    }
+
+    /// Approve a council proposal
+    pub fn approve(env: Env, proposal_id: u64, approver: Address) -> Result<(), GovernanceError> {
+        approver.require_auth();
+
+        GovernanceManager::approve_proposal(&env, proposal_id, approver)
+    }
+
+    /// Record several approvals at once from off-chain ed25519 signatures (anyone can relay)
+    pub fn approve_with_signatures(
+        env: Env,
+        proposal_id: u64,
+        signatures: Vec<ApprovalSignature>,
+    ) -> Result<u32, GovernanceError> {
+        GovernanceManager::approve_with_signatures(&env, proposal_id, signatures)
+    }
+
+    /// Get the digest council members sign to approve a proposal
+    pub fn get_approval_digest(env: Env, proposal_id: u64) -> BytesN<32> {
+        GovernanceManager::approval_digest(&env, proposal_id)
+    }
+
+    /// Register the ed25519 key a council member signs off-chain approvals with
+    pub fn set_signing_key(
+        env: Env,
+        approver: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), GovernanceError> {
+        approver.require_auth();
+
+        GovernanceManager::set_signing_key(&env, &approver, &public_key)
+    }
+
+    /// Vote to reject a council proposal
+    pub fn reject(env: Env, proposal_id: u64, rejector: Address) -> Result<(), GovernanceError> {
+        rejector.require_auth();
+
+        GovernanceManager::reject_proposal(&env, proposal_id, rejector)
+    }
+
+    /// Withdraw an approval before the proposal's timelock ends
+    pub fn revoke_approval(
+        env: Env,
+        proposal_id: u64,
+        approver: Address,
+    ) -> Result<(), GovernanceError> {
+        approver.require_auth();
+
+        GovernanceManager::revoke_approval(&env, proposal_id, approver)
+    }
+
+    /// Hand an approver's vote to a stand-in until `until` (max 90 days)
+    pub fn delegate_approval(
+        env: Env,
+        delegator: Address,
+        delegate: Address,
+        until: u64,
+    ) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+
+        GovernanceManager::delegate_approval(&env, delegator, delegate, until)
+    }
+
+    /// End an approver's delegation early
+    pub fn revoke_delegation(env: Env, delegator: Address) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+
+        GovernanceManager::revoke_delegation(&env, delegator)
+    }
+
+    /// Approve for the approver who delegated to `delegate`; counts for their seat
+    pub fn approve_as_delegate(
+        env: Env,
+        proposal_id: u64,
+        delegate: Address,
+        delegator: Address,
+    ) -> Result<(), GovernanceError> {
+        delegate.require_auth();
+
+        GovernanceManager::approve_as_delegate(&env, proposal_id, delegate, delegator)
+    }
+
+    /// Get an approver's active delegation
+    pub fn get_delegation(env: Env, delegator: Address) -> Option<ApprovalDelegation> {
+        GovernanceManager::get_delegation(&env, &delegator)
+    }
+
+    /// Execute an approved proposal after its timelock
+    pub fn execute(env: Env, proposal_id: u64, executor: Address) -> Result<(), GovernanceError> {
+        executor.require_auth();
+
+        GovernanceManager::execute_proposal(&env, proposal_id, executor)
+    }
+
+    /// Veto an approved proposal before its timelock ends (Guardian, rate-limited)
+    pub fn veto(env: Env, proposal_id: u64, guardian: Address) -> Result<(), GovernanceError> {
+        guardian.require_auth();
+
+        GovernanceManager::veto_proposal(&env, proposal_id, guardian)
+    }
+
+    /// Cancel a proposal (admin only)
+    pub fn cancel(env: Env, proposal_id: u64, admin: Address) -> Result<(), GovernanceError> {
+        admin.require_auth();
+
+        GovernanceManager::cancel_proposal(&env, proposal_id, admin)
+    }
+
+    /// Mark a proposal that missed its voting deadline or execution window as expired
+    pub fn expire(env: Env, proposal_id: u64) -> Result<(), GovernanceError> {
+        GovernanceManager::expire_proposal(&env, proposal_id)
+    }
+
+    /// Get proposal details
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<UpgradeProposal, GovernanceError> {
+        GovernanceManager::get_proposal(&env, proposal_id)
+    }
+
+    /// List proposals after `cursor`, optionally filtered by status
+    pub fn list_proposals(
+        env: Env,
+        status: Option<ProposalStatus>,
+        cursor: u64,
+        limit: u32,
+    ) -> ProposalPage {
+        GovernanceManager::list_proposals(&env, status, cursor, limit)
+    }
+
+    /// Get every approver's recorded vote on a proposal
+    pub fn get_council_votes(
+        env: Env,
+        proposal_id: u64,
+    ) -> Result<Vec<ApproverVote>, GovernanceError> {
+        GovernanceManager::get_council_votes(&env, proposal_id)
+    }
+
+    /// Get the approver council that decides new proposals
+    pub fn get_council(env: Env) -> Council {
+        CouncilManager::get(&env)
+    }
+
+    /// Get a governance-managed parameter
+    pub fn get_param(env: Env, key: Symbol) -> Option<i128> {
+        GovernanceManager::get_param(&env, key)
+    }
+
+    /// Get every scheduled change to a parameter, oldest first
+    pub fn get_param_history(env: Env, key: Symbol) -> Vec<ParamChange> {
+        ParamStore::history(&env, &key)
+    }
+
+    /// Get the registry entry for a trusted call target
+    pub fn get_trusted_target(env: Env, contract: Address) -> Option<TrustedTarget> {
+        TargetRegistry::get(&env, &contract)
+    }
+
+    /// Whether calls and fee tokens must be registered trusted targets
+    pub fn requires_trusted_targets(env: Env) -> bool {
+        TargetRegistry::is_required(&env)
+    }
+
+    /// List every address holding a role
+    pub fn get_role_members(env: Env, role: GovernanceRole) -> Vec<Address> {
+        RoleManager::members(&env, &role)
+    }
+
+    /// Check whether an address holds a role
+    pub fn has_role(env: Env, address: Address, role: GovernanceRole) -> bool {
+        RoleManager::has_role(&env, &address, &role)
+    }
+
+    /// Get the role whose holders may propose changes to `role`
+    pub fn get_role_admin(env: Env, role: GovernanceRole) -> GovernanceRole {
+        RoleManager::role_admin(&env, &role)
+    }
+
+    /// Enable token-weighted voting (admin only, once; later changes go through a proposal)
+    pub fn configure_token_voting(
+        env: Env,
+        admin: Address,
+        config: VotingConfig,
+    ) -> Result<(), GovernanceError> {
+        admin.require_auth();
+        GovernanceManager::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+        if TokenVoting::get_config(&env).is_some() {
+            return Err(GovernanceError::Unauthorized);
+        }
+
+        TokenVoting::set_config(&env, &config)
+    }
+
+    /// Get the token voting configuration
+    pub fn get_voting_config(env: Env) -> Option<VotingConfig> {
+        TokenVoting::get_config(&env)
+    }
+
+    /// Open a token-holder vote on an action
+    pub fn propose_token_vote(
+        env: Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+        Self::check_actions(&soroban_sdk::vec![&env, action.clone()])?;
+
+        TokenVoting::propose(&env, proposer, action, description)
+    }
+
+    /// Vote on a token-holder proposal; returns the voting power counted
+    pub fn cast_vote(
+        env: Env,
+        proposal_id: u64,
+        voter: Address,
+        choice: VoteChoice,
+        reason: Symbol,
+    ) -> Result<i128, GovernanceError> {
+        voter.require_auth();
+
+        TokenVoting::cast_vote(&env, proposal_id, voter, choice, reason)
+    }
+
+    /// Close a token-holder vote after its voting period
+    pub fn finalize_vote(env: Env, proposal_id: u64) -> Result<ProposalStatus, GovernanceError> {
+        TokenVoting::finalize(&env, proposal_id)
+    }
+
+    /// Get the running tally of a token-holder vote
+    pub fn get_vote_tally(env: Env, proposal_id: u64) -> VoteTally {
+        TokenVoting::get_tally(&env, proposal_id)
+    }
+
+    /// Get the quorum and pass threshold a token-holder vote is decided by
+    pub fn get_vote_terms(env: Env, proposal_id: u64) -> Result<VoteTerms, GovernanceError> {
+        TokenVoting::get_terms(&env, proposal_id)
+    }
+
+    /// Get a voter's ballot on a token-holder vote
+    pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<VoteRecord> {
+        TokenVoting::get_vote(&env, proposal_id, voter)
+    }
+
+    /// Internal helper: refuse actions nothing reads here.
+    ///
+    /// Upgrades, rollbacks and pauses would only be recorded in this contract's
+    /// storage, and new parameters would have no reader; propose a `Call` to
+    /// the governed contract instead.
+    fn check_actions(actions: &Vec<ProposalAction>) -> Result<(), GovernanceError> {
+        for action in actions.iter() {
+            if matches!(
+                action,
+                ProposalAction::Upgrade(_)
+                    | ProposalAction::Rollback
+                    | ProposalAction::Pause(_, _)
+                    | ProposalAction::ExtendPause(_, _)
+                    | ProposalAction::Unpause(_)
+                    | ProposalAction::DefineParam(_, _, _)
+            ) {
+                return Err(GovernanceError::InvalidAction);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,243 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, testutils::Ledger, IntoVal};
+use shared::governance::{EMERGENCY_CALL_SELECTOR, MIN_TIMELOCK_PARAM};
+use shared::params::ParamSpec;
+use shared::pause::PauseScope;
+
+/// Stand-in for a governed contract: only its admin may pause it
+#[contract]
+pub struct GovernedContract;
+
+#[contractimpl]
+impl GovernedContract {
+    pub fn set_admin(env: Env, admin: Address) {
+        env.storage().instance().set(&symbol_short!("admin"), &admin);
+    }
+
+    pub fn pause(env: Env, caller: Address) -> Result<(), GovernanceError> {
+        caller.require_auth();
+        let admin: Option<Address> = env.storage().instance().get(&symbol_short!("admin"));
+        if admin != Some(caller) {
+            return Err(GovernanceError::Unauthorized);
+        }
+        env.storage().instance().set(&symbol_short!("paused"), &true);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("paused")).unwrap_or(false)
+    }
+}
+
+fn setup_governance(env: &Env) -> (GovernanceContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let contract_id = env.register_contract(None, GovernanceContract);
+    let client = GovernanceContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let approver = Address::generate(env);
+    let executor = Address::generate(env);
+    client.init(&admin, &soroban_sdk::vec![env, approver.clone()], &executor);
+
+    (client, admin, approver, executor)
+}
+
+#[test]
+fn test_actions_with_no_reader_are_refused() {
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let desc = symbol_short!("local");
+    let spec = ParamSpec { min: 0, max: 10, change_delay: 0 };
+
+    for action in [
+        ProposalAction::Upgrade(symbol_short!("v2hash")),
+        ProposalAction::Pause(PauseScope::Global, 3_600),
+        ProposalAction::Unpause(PauseScope::Global),
+        ProposalAction::DefineParam(symbol_short!("fee"), spec, 1),
+    ] {
+        assert_eq!(client.try_propose(&admin, &action, &desc), Err(Ok(GovernanceError::InvalidAction)));
+    }
+
+    // A refused action can't ride along in a batch or an emergency either
+    let batch = soroban_sdk::vec![
+        &env,
+        ProposalAction::GrantRole(Address::generate(&env), GovernanceRole::Compliance),
+        ProposalAction::Pause(PauseScope::Global, 3_600),
+    ];
+    assert_eq!(client.try_propose_batch(&admin, &batch, &desc), Err(Ok(GovernanceError::InvalidAction)));
+    assert_eq!(
+        client.try_propose_emergency(&admin, &ProposalAction::Pause(PauseScope::Global, 3_600), &desc),
+        Err(Ok(GovernanceError::InvalidAction))
+    );
+
+    // Only parameters defined here, i.e. the timelocks, can be changed
+    assert_eq!(
+        client.try_propose(&admin, &ProposalAction::SetParam(symbol_short!("fee"), 1), &desc),
+        Err(Ok(GovernanceError::UnknownParam))
+    );
+    client.propose(&admin, &ProposalAction::SetParam(MIN_TIMELOCK_PARAM, 7_200), &desc);
+}
+
+#[test]
+fn test_emergency_pause_call_only_reaches_trusted_targets() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let governed = env.register_contract(None, GovernedContract);
+    let governed_client = GovernedContractClient::new(&env, &governed);
+    governed_client.set_admin(&client.address);
+
+    let args = soroban_sdk::vec![&env, client.address.into_val(&env)];
+    let pause = ProposalAction::Call(governed.clone(), EMERGENCY_CALL_SELECTOR, args.clone());
+    let desc = symbol_short!("halt");
+
+    // Not registered yet
+    assert_eq!(client.try_propose_emergency(&admin, &pause, &desc), Err(Ok(GovernanceError::InvalidAction)));
+
+    let trust = client.propose(&admin, &ProposalAction::TrustTarget(governed.clone()), &symbol_short!("trust"));
+    client.approve(&trust, &approver);
+    let unlocked_at = client.get_proposal(&trust).execution_time;
+    env.ledger().with_mut(|li| li.timestamp = unlocked_at);
+    client.execute(&trust, &executor);
+
+    // Registered, but only the pause selector qualifies
+    let other = ProposalAction::Call(governed.clone(), Symbol::new(&env, "set_admin"), args);
+    assert_eq!(client.try_propose_emergency(&admin, &other, &desc), Err(Ok(GovernanceError::InvalidAction)));
+
+    let id = client.propose_emergency(&admin, &pause, &desc);
+    client.approve(&id, &approver);
+    client.execute(&id, &executor);
+    assert!(governed_client.is_paused());
+}
+
+#[test]
+fn test_call_acts_on_governed_contract_as_its_admin() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let governed = env.register_contract(None, GovernedContract);
+    let governed_client = GovernedContractClient::new(&env, &governed);
+    governed_client.set_admin(&client.address);
+
+    // Nobody but the governance contract can pause it
+    let outsider = Address::generate(&env);
+    assert_eq!(governed_client.try_pause(&outsider), Err(Ok(GovernanceError::Unauthorized)));
+
+    let args = soroban_sdk::vec![&env, client.address.into_val(&env)];
+    let id = client.propose_call(&admin, &governed, &Symbol::new(&env, "pause"), &args, &symbol_short!("pause"));
+    client.approve(&id, &approver);
+
+    let prop = client.get_proposal(&id);
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time);
+    client.execute(&id, &executor);
+
+    assert!(governed_client.is_paused());
+    assert_eq!(client.get_proposal(&id).status, ProposalStatus::Executed);
+}
+
+/// Propose `actions` as one batch, approve, wait out the timelock and execute
+fn run_batch(
+    env: &Env,
+    client: &GovernanceContractClient,
+    roles: (&Address, &Address, &Address),
+    actions: Vec<ProposalAction>,
+) -> u64 {
+    let (admin, approver, executor) = roles;
+    let id = client.propose_batch(admin, &actions, &symbol_short!("batch"));
+    client.approve(&id, approver);
+    let unlocked_at = client.get_proposal(&id).execution_time;
+    env.ledger().with_mut(|li| li.timestamp = li.timestamp.max(unlocked_at));
+    client.execute(&id, executor);
+    id
+}
+
+#[test]
+fn test_trading_proposals_and_roles_run_through_governance() {
+    use trading::{UpgradeableTradingContract, UpgradeableTradingContractClient};
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let roles = (&admin, &approver, &executor);
+    let gov = client.address.clone();
+
+    // The governance contract holds trading's admin, whole council and executor
+    // seats, so a trading proposal only moves when governance calls it along
+    let trading_id = env.register_contract(None, UpgradeableTradingContract);
+    let trading = UpgradeableTradingContractClient::new(&env, &trading_id);
+    trading.init(&gov, &soroban_sdk::vec![&env, gov.clone()], &gov);
+
+    let compliance = Address::generate(&env);
+    let call = |function: &str, args: Vec<Val>| ProposalAction::Call(trading_id.clone(), Symbol::new(&env, function), args);
+    let propose = call(
+        "propose_action",
+        soroban_sdk::vec![
+            &env,
+            gov.into_val(&env),
+            ProposalAction::GrantRole(compliance.clone(), GovernanceRole::Compliance).into_val(&env),
+            symbol_short!("kyc").into_val(&env),
+        ],
+    );
+    let approve = call("approve_upgrade", soroban_sdk::vec![&env, 1u64.into_val(&env), gov.into_val(&env)]);
+    run_batch(&env, &client, roles, soroban_sdk::vec![&env, propose, approve]);
+    assert_eq!(trading.get_upgrade_proposal(&1).status, ProposalStatus::Approved);
+
+    // Trading's own timelock still applies on top of governance's
+    let unlocked_at = trading.get_upgrade_proposal(&1).execution_time;
+    env.ledger().with_mut(|li| li.timestamp = li.timestamp.max(unlocked_at));
+    let execute = call("execute_upgrade", soroban_sdk::vec![&env, 1u64.into_val(&env), gov.into_val(&env)]);
+    run_batch(&env, &client, roles, soroban_sdk::vec![&env, execute]);
+
+    assert_eq!(trading.get_upgrade_proposal(&1).status, ProposalStatus::Executed);
+    assert!(trading.has_role(&compliance, &GovernanceRole::Compliance));
+    assert_eq!(client.list_proposals(&Some(ProposalStatus::Executed), &0, &10).proposals.len(), 2);
+}
+
+#[test]
+fn test_vesting_and_rewards_admin_calls_run_through_governance() {
+    use academy_rewards::{AcademyRewardsContract, AcademyRewardsContractClient};
+    use academy_vesting::vesting::{AcademyVestingContractClient, REVOKE_DELAY_PARAM};
+    use academy_vesting::AcademyVestingContract;
+    use shared::ratelimit::RateLimit;
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let gov = client.address.clone();
+
+    // Governance is both the vesting admin and its parameter governance
+    let vesting_id = env.register_contract(None, AcademyVestingContract);
+    let vesting = AcademyVestingContractClient::new(&env, &vesting_id);
+    vesting.init(&gov, &env.register_stellar_asset_contract(admin.clone()), &gov);
+
+    let rewards_id = env.register_contract(None, AcademyRewardsContract);
+    let rewards = AcademyRewardsContractClient::new(&env, &rewards_id);
+    rewards.initialize(&gov);
+
+    let beneficiary = Address::generate(&env);
+    let limit = RateLimit { capacity: 2, window: 86_400 };
+    let actions = soroban_sdk::vec![
+        &env,
+        ProposalAction::Call(
+            vesting_id.clone(),
+            Symbol::new(&env, "grant_vesting"),
+            (gov.clone(), beneficiary.clone(), 1_000i128, 0u64, 100u64, 3_600u64).into_val(&env),
+        ),
+        ProposalAction::Call(
+            vesting_id.clone(),
+            Symbol::new(&env, "set_param"),
+            (REVOKE_DELAY_PARAM, 7_200i128).into_val(&env),
+        ),
+        ProposalAction::Call(
+            rewards_id.clone(),
+            Symbol::new(&env, "set_redemption_rate"),
+            (gov.clone(), Some(limit.clone())).into_val(&env),
+        ),
+    ];
+    let id = run_batch(&env, &client, (&admin, &approver, &executor), actions);
+
+    assert_eq!(vesting.get_vesting(&1).beneficiary, beneficiary);
+    assert_eq!(vesting.get_param_history(&REVOKE_DELAY_PARAM).get(0).unwrap().new_value, 7_200);
+    assert_eq!(rewards.get_redemption_rate(), Some(limit));
+    assert_eq!(client.get_proposal(&id).status, ProposalStatus::Executed);
+}
@@ -0,0 +1,266 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+use crate::TradeError;
+
+/// Most orders a single auction accepts, to keep settlement bounded
+pub const MAX_AUCTION_ORDERS: u32 = 100;
+
+/// Most orders one trader may have in a single auction, so one account can't fill the book
+pub const MAX_ORDERS_PER_TRADER: u32 = 5;
+
+/// Open auction round for a pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub id: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Limit order as submitted to `place_order`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderRequest {
+    pub pair: Symbol,
+    pub amount: i128,
+    pub limit_price: i128,
+    pub is_buy: bool,
+}
+
+/// Limit order waiting for its auction to clear
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionOrder {
+    pub trader: Address,
+    pub amount: i128,
+    pub limit_price: i128,
+    pub is_buy: bool,
+    pub placed_at: u64,
+}
+
+/// Settlement outcome, also emitted for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionResult {
+    pub pair: Symbol,
+    pub auction_id: u64,
+    pub clearing_price: i128,
+    pub volume: i128,
+    pub fills: u32,
+    pub settled_at: u64,
+}
+
+pub struct AuctionBook;
+
+impl AuctionBook {
+    /// Auction window for a pair in seconds (0 = continuous trading)
+    pub fn window(env: &Env, pair: &Symbol) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("auctwin"), pair.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Set the auction window for a pair
+    pub fn set_window(env: &Env, pair: &Symbol, window: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("auctwin"), pair.clone()), &window);
+    }
+
+    /// Currently open (or closed but unsettled) auction for a pair
+    pub fn current(env: &Env, pair: &Symbol) -> Option<Auction> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("auction"), pair.clone()))
+    }
+
+    /// Open the next auction round for a pair
+    pub fn open(env: &Env, pair: &Symbol, window: u64) -> Auction {
+        let counter_key = (symbol_short!("auctcnt"), pair.clone());
+        let id: u64 = env.storage().persistent().get(&counter_key).unwrap_or(0u64) + 1;
+        env.storage().persistent().set(&counter_key, &id);
+
+        let now = env.ledger().timestamp();
+        let auction = Auction {
+            id,
+            start: now,
+            end: now + window,
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("auction"), pair.clone()), &auction);
+
+        auction
+    }
+
+    /// Close the current auction round, dropping its order book
+    pub fn close(env: &Env, pair: &Symbol, auction: &Auction) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("auction"), pair.clone()));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("aorders"), pair.clone(), auction.id));
+    }
+
+    /// Orders collected for an auction round
+    pub fn orders(env: &Env, pair: &Symbol, auction_id: u64) -> Vec<AuctionOrder> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("aorders"), pair.clone(), auction_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Store the orders collected for an auction round
+    pub fn set_orders(env: &Env, pair: &Symbol, auction_id: u64, orders: &Vec<AuctionOrder>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("aorders"), pair.clone(), auction_id), orders);
+    }
+
+    /// Orders a trader already has in an auction round
+    pub fn orders_by(orders: &Vec<AuctionOrder>, trader: &Address) -> u32 {
+        orders.iter().filter(|order| order.trader == *trader).count() as u32
+    }
+
+    /// Find the uniform price that maximizes matched volume.
+    ///
+    /// Ties go to the smallest demand/supply imbalance, then the lowest price.
+    /// Returns `(price, volume)`, with zero volume when the book does not cross.
+    pub fn clearing_price(orders: &Vec<AuctionOrder>) -> Result<(i128, i128), TradeError> {
+        let mut best_price = 0i128;
+        let mut best_volume = 0i128;
+        let mut best_imbalance = 0i128;
+
+        for candidate in orders.iter() {
+            let price = candidate.limit_price;
+            let mut demand = 0i128;
+            let mut supply = 0i128;
+
+            for order in orders.iter() {
+                if order.is_buy && order.limit_price >= price {
+                    demand = demand.checked_add(order.amount).ok_or(TradeError::ArithmeticOverflow)?;
+                } else if !order.is_buy && order.limit_price <= price {
+                    supply = supply.checked_add(order.amount).ok_or(TradeError::ArithmeticOverflow)?;
+                }
+            }
+
+            // Both sides are non-negative, so the difference can't overflow
+            let volume = demand.min(supply);
+            let imbalance = (demand - supply).abs();
+            let better = volume > best_volume
+                || (volume == best_volume
+                    && volume > 0
+                    && (imbalance < best_imbalance
+                        || (imbalance == best_imbalance && price < best_price)));
+
+            if better {
+                best_price = price;
+                best_volume = volume;
+                best_imbalance = imbalance;
+            }
+        }
+
+        Ok((best_price, best_volume))
+    }
+
+    /// Allocate `volume` across one side of the book at the clearing price.
+    ///
+    /// Orders with better limits fill first; the marginal price level shares
+    /// what is left pro-rata, with rounding dust going to the earliest orders.
+    /// Returns a fill amount per order, in order-book order.
+    pub fn allocate(
+        env: &Env,
+        orders: &Vec<AuctionOrder>,
+        is_buy: bool,
+        clearing_price: i128,
+        volume: i128,
+    ) -> Result<Vec<i128>, TradeError> {
+        let mut fills = Vec::new(env);
+        for _ in orders.iter() {
+            fills.push_back(0i128);
+        }
+
+        let eligible = |order: &AuctionOrder| {
+            order.is_buy == is_buy
+                && if is_buy {
+                    order.limit_price >= clearing_price
+                } else {
+                    order.limit_price <= clearing_price
+                }
+        };
+
+        // Distinct eligible price levels, best first
+        let mut levels: Vec<i128> = Vec::new(env);
+        for order in orders.iter() {
+            if eligible(&order) && !levels.contains(order.limit_price) {
+                let mut index = 0u32;
+                while index < levels.len() {
+                    let level = levels.get_unchecked(index);
+                    let before = if is_buy {
+                        order.limit_price > level
+                    } else {
+                        order.limit_price < level
+                    };
+                    if before {
+                        break;
+                    }
+                    index += 1;
+                }
+                levels.insert(index, order.limit_price);
+            }
+        }
+
+        let mut remaining = volume;
+        for level in levels.iter() {
+            if remaining == 0 {
+                break;
+            }
+
+            let mut level_total = 0i128;
+            for order in orders.iter() {
+                if eligible(&order) && order.limit_price == level {
+                    level_total = level_total.checked_add(order.amount).ok_or(TradeError::ArithmeticOverflow)?;
+                }
+            }
+
+            if level_total <= remaining {
+                for (index, order) in orders.iter().enumerate() {
+                    if eligible(&order) && order.limit_price == level {
+                        fills.set(index as u32, order.amount);
+                    }
+                }
+                remaining -= level_total;
+                continue;
+            }
+
+            // Marginal level: pro-rata, then hand out rounding dust in time order
+            let mut allocated = 0i128;
+            for (index, order) in orders.iter().enumerate() {
+                if eligible(&order) && order.limit_price == level {
+                    let fill = remaining
+                        .checked_mul(order.amount)
+                        .ok_or(TradeError::ArithmeticOverflow)?
+                        / level_total;
+                    fills.set(index as u32, fill);
+                    allocated += fill;
+                }
+            }
+            let mut dust = remaining - allocated;
+            for (index, order) in orders.iter().enumerate() {
+                if dust == 0 {
+                    break;
+                }
+                let fill = fills.get_unchecked(index as u32);
+                if eligible(&order) && order.limit_price == level && fill < order.amount {
+                    fills.set(index as u32, fill + 1);
+                    dust -= 1;
+                }
+            }
+            remaining = 0;
+        }
+
+        Ok(fills)
+    }
+}
@@ -0,0 +1,96 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, IntoVal, Symbol, Vec};
+use shared::safe_call::safe_invoke_typed;
+
+/// Who may trade on a pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EligibilityMode {
+    /// Anyone can trade
+    Open,
+    /// Only traders on the pair's allowlist (kept by the compliance role)
+    Allowlist,
+    /// Traders attested by an external registry contract
+    Attestation(Address),
+}
+
+/// Allowlist change event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AllowlistEvent {
+    pub pair: Symbol,
+    pub traders: Vec<Address>,
+    pub updated_by: Address,
+    pub updated_at: u64,
+}
+
+/// Outcome of an eligibility check
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Eligibility {
+    Eligible,
+    NotEligible,
+    /// The attestation registry could not be queried
+    Unavailable,
+}
+
+pub struct Compliance;
+
+impl Compliance {
+    /// Get the eligibility mode for a pair (defaults to open)
+    pub fn get_mode(env: &Env, pair: &Symbol) -> EligibilityMode {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("elig"), pair.clone()))
+            .unwrap_or(EligibilityMode::Open)
+    }
+
+    /// Set the eligibility mode for a pair
+    pub fn set_mode(env: &Env, pair: &Symbol, mode: &EligibilityMode) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("elig"), pair.clone()), mode);
+    }
+
+    /// Check whether a trader is on a pair's allowlist
+    pub fn is_allowlisted(env: &Env, pair: &Symbol, trader: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("allow"), pair.clone(), trader.clone()))
+    }
+
+    /// Add or remove a batch of traders from a pair's allowlist
+    pub fn update_allowlist(env: &Env, pair: &Symbol, traders: &Vec<Address>, allowed: bool) {
+        for trader in traders.iter() {
+            let key = (symbol_short!("allow"), pair.clone(), trader);
+            if allowed {
+                env.storage().persistent().set(&key, &true);
+            } else {
+                env.storage().persistent().remove(&key);
+            }
+        }
+    }
+
+    /// Check a trader against the pair's eligibility mode
+    pub fn check(env: &Env, pair: &Symbol, trader: &Address) -> Eligibility {
+        match Self::get_mode(env, pair) {
+            EligibilityMode::Open => Eligibility::Eligible,
+            EligibilityMode::Allowlist => {
+                if Self::is_allowlisted(env, pair, trader) {
+                    Eligibility::Eligible
+                } else {
+                    Eligibility::NotEligible
+                }
+            }
+            EligibilityMode::Attestation(registry) => {
+                // Registry interface: is_eligible(trader: Address, pair: Symbol) -> bool
+                let args = soroban_sdk::vec![env, trader.into_val(env), pair.into_val(env)];
+                let func = Symbol::new(env, "is_eligible");
+
+                match safe_invoke_typed::<bool>(env, &registry, &func, args) {
+                    Ok(true) => Eligibility::Eligible,
+                    Ok(false) => Eligibility::NotEligible,
+                    Err(_) => Eligibility::Unavailable,
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,48 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, symbol_short};
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Address, BytesN, Env, Symbol, symbol_short};
+use shared::council::{Council, CouncilManager};
 use shared::fees::{FeeManager, FeeError};
 use shared::governance::{
-    GovernanceManager, GovernanceRole, UpgradeProposal,
+    ApprovalDelegation, ApprovalSignature, ApproverVote, GovernanceError, GovernanceManager, GovernanceRole, ProposalAction,
+    ProposalPage, ProposalStatus, UpgradeProposal,
 };
+use shared::guard::{GuardError, ReentrancyGuard};
+use shared::params::{ParamChange, ParamSpec, ParamStore};
+use shared::pause::{PauseEvent, PauseManager, PauseScope, PauseState, MAX_PAUSE_DURATION, PAUSE_COOLDOWN};
+use shared::ratelimit::{RateLimit, RateLimitError, RateLimiter, MAX_WINDOW};
+use shared::roles::RoleManager;
+use shared::safe_call::{TargetRegistry, TrustedTarget};
+use shared::upgrade::{UpgradeManager, UpgradeRecord};
+use shared::voting::{TokenVoting, VoteChoice, VoteRecord, VoteTally, VoteTerms, VotingConfig};
+
+mod auction;
+mod compliance;
+mod market_data;
+
+pub use auction::{
+    Auction, AuctionOrder, AuctionResult, OrderRequest, MAX_AUCTION_ORDERS, MAX_ORDERS_PER_TRADER,
+};
+pub use compliance::{AllowlistEvent, EligibilityMode};
+pub use market_data::{Candle, PriceObservation, MAX_CANDLE_INTERVALS};
+use auction::AuctionBook;
+use compliance::{Compliance, Eligibility};
+use market_data::MarketData;
 
 /// Version of this contract implementation
 const CONTRACT_VERSION: u32 = 1;
 
+/// Parameter key for the per-trader volume allowance per window (unlimited while undefined)
+pub const VOLUME_CAP_PARAM: Symbol = symbol_short!("vol_cap");
+
+/// Parameter key for the volume window in seconds (1 second to `ratelimit::MAX_WINDOW`)
+pub const VOLUME_WINDOW_PARAM: Symbol = symbol_short!("vol_win");
+
+/// Starting volume window: 1 day
+pub const DEFAULT_VOLUME_WINDOW: u64 = 86_400;
+
+/// Parameter key for the smallest auction order amount (any positive amount while undefined)
+pub const AUCTION_MIN_ORDER_PARAM: Symbol = symbol_short!("auct_min");
+
 /// Trading contract with upgradeability and governance
 #[contract]
 pub struct UpgradeableTradingContract;
@@ -34,6 +69,8 @@ pub struct TradeStats {
     pub last_trade_id: u64,
 }
 
+/// Trading error codes (`shared::errors::TRADE_ERRORS` block)
+#[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum TradeError {
@@ -41,60 +78,83 @@ pub enum TradeError {
     InvalidAmount = 3002,
     ContractPaused = 3003,
     NotInitialized = 3004,
+    TraderNotEligible = 3005,
+    AttestationUnavailable = 3006,
+    InsufficientBalance = 3007,
+    InvalidPauseDuration = 3008,
+    NotPaused = 3009,
+    InvalidInterval = 3013,
+    InvalidWindow = 3014,
+    InsufficientHistory = 3015,
+    AuctionModeActive = 3016,
+    AuctionNotFound = 3017,
+    AuctionNotClosed = 3018,
+    AuctionPendingSettlement = 3019,
+    AuctionFull = 3020,
+    UntrustedToken = 3021,
+    Reentered = 3022,
+    RateLimited = 3023,
+    AlreadyPaused = 3024,
+    ArithmeticOverflow = 3025,
+    OrderTooSmall = 3026,
+    TraderOrderLimit = 3027,
+    PauseCooldown = 3028,
 }
 
-impl From<TradeError> for soroban_sdk::Error {
-    fn from(error: TradeError) -> Self {
-        soroban_sdk::Error::from_contract_error(error as u32)
+impl From<FeeError> for TradeError {
+    fn from(error: FeeError) -> Self {
+        match error {
+            FeeError::InsufficientBalance => TradeError::InsufficientBalance,
+            FeeError::InvalidAmount => TradeError::InvalidAmount,
+            FeeError::UntrustedToken => TradeError::UntrustedToken,
+            FeeError::Reentered => TradeError::Reentered,
+        }
     }
 }
 
-impl From<&TradeError> for soroban_sdk::Error {
-    fn from(error: &TradeError) -> Self {
-        soroban_sdk::Error::from_contract_error(*error as u32)
+impl From<RateLimitError> for TradeError {
+    fn from(_: RateLimitError) -> Self {
+        TradeError::RateLimited
     }
 }
 
-impl From<soroban_sdk::Error> for TradeError {
-    fn from(_error: soroban_sdk::Error) -> Self {
-        TradeError::Unauthorized
+impl From<GuardError> for TradeError {
+    fn from(_: GuardError) -> Self {
+        TradeError::Reentered
     }
 }
 
 #[contractimpl]
 impl UpgradeableTradingContract {
-    /// Initialize the contract with admin and initial approvers
+    /// Initialize the contract with admin and the initial approver council (majority thresholds)
     pub fn init(
         env: Env,
         admin: Address,
         approvers: soroban_sdk::Vec<Address>,
         executor: Address,
-    ) -> Result<(), TradeError> {
+    ) -> Result<(), GovernanceError> {
         // Check if already initialized
         let init_key = symbol_short!("init");
         if env.storage().persistent().has(&init_key) {
-            return Err(TradeError::Unauthorized);
+            return Err(GovernanceError::Unauthorized);
         }
 
         // Set initialization flag
         env.storage().persistent().set(&init_key, &true);
 
         // Store roles
-        let roles_key = symbol_short!("roles");
-        let mut roles = soroban_sdk::Map::new(&env);
-
-        // Set admin role
-        roles.set(admin, GovernanceRole::Admin);
-
-        // Set approvers
-        for approver in approvers.iter() {
-            roles.set(approver, GovernanceRole::Approver);
-        }
+        RoleManager::grant(&env, &admin, &GovernanceRole::Admin);
 
-        // Set executor
-        roles.set(executor, GovernanceRole::Executor);
+        let council = Council {
+            threshold: CouncilManager::majority(approvers.len()),
+            veto_threshold: CouncilManager::majority(approvers.len()),
+            members: approvers,
+        };
+        CouncilManager::set(&env, &council)?;
 
-        env.storage().persistent().set(&roles_key, &roles);
+        RoleManager::grant(&env, &executor, &GovernanceRole::Executor);
+        GovernanceManager::define_timelocks(&env)?;
+        Self::define_params(&env)?;
 
         // Initialize stats
         let stats = TradeStats {
@@ -113,6 +173,8 @@ impl UpgradeableTradingContract {
     }
 
     /// Execute a trade with fee collection
+    // Kept flat: this signature predates `OrderRequest` and clients already call it
+    #[allow(clippy::too_many_arguments)]
     pub fn trade(
         env: Env,
         trader: Address,
@@ -123,69 +185,48 @@ impl UpgradeableTradingContract {
         fee_token: Address,
         fee_amount: i128,
         fee_recipient: Address,
-    ) -> Result<u64, FeeError> {
+    ) -> Result<u64, TradeError> {
         trader.require_auth();
 
-        // Verify not paused
-        let paused_key = symbol_short!("pause");
-        let is_paused: bool = env
-            .storage()
-            .persistent()
-            .get(&paused_key)
-            .unwrap_or(false);
+        // Calls out to the caller-chosen fee token and the admin-set attestation registry
+        let _guard = ReentrancyGuard::enter(&env, symbol_short!("trade"))?;
 
-        if is_paused {
-            panic!("PAUSED");
+        // Verify not paused globally, for this pair, or for trade()
+        if PauseManager::is_blocked(&env, symbol_short!("trade"), Some(pair.clone())) {
+            return Err(TradeError::ContractPaused);
         }
 
-        // Collect fee first
-        FeeManager::collect_fee(&env, &fee_token, &trader, &fee_recipient, fee_amount)?;
+        if amount <= 0 || price <= 0 {
+            return Err(TradeError::InvalidAmount);
+        }
 
-        // Create trade record
-        let stats_key = symbol_short!("stats");
-        let mut stats: TradeStats = env
-            .storage()
-            .persistent()
-            .get(&stats_key)
-            .unwrap_or(TradeStats {
-                total_trades: 0,
-                total_volume: 0,
-                last_trade_id: 0,
-            });
+        // Pairs in batch auction mode only accept orders through place_order()
+        if AuctionBook::window(&env, &pair) > 0 {
+            return Err(TradeError::AuctionModeActive);
+        }
 
-        let trade_id = stats.last_trade_id + 1;
-        let trade = Trade {
-            id: trade_id,
-            trader,
-            pair,
-            amount,
-            price,
-            timestamp: env.ledger().timestamp(),
-            is_buy,
-        };
+        // Verify trader is allowed on this pair
+        Self::require_eligible(&env, &pair, &trader)?;
 
-        // Update stats
-        stats.total_trades += 1;
-        stats.total_volume += amount;
-        stats.last_trade_id = trade_id;
+        Self::spend_volume(&env, &trader, amount)?;
 
-        // Store trade
-        let trades_key = symbol_short!("trades");
-        let mut trades: soroban_sdk::Vec<Trade> = env
-            .storage()
-            .persistent()
-            .get(&trades_key)
-            .unwrap_or_else(|| soroban_sdk::Vec::new(&env));
+        // Collect fee first
+        FeeManager::collect_fee(&env, &fee_token, &trader, &fee_recipient, fee_amount)?;
 
-        trades.push_back(trade);
+        // Update candles and the TWAP accumulator
+        MarketData::record_trade(&env, &pair, price, amount)?;
 
-        // Update persistent storage
-        env.storage().persistent().set(&trades_key, &trades);
-        env.storage().persistent().set(&stats_key, &stats);
+        let trade_id = Self::store_trade(&env, trader, pair, amount, price, is_buy)?;
 
         Ok(trade_id)
     }
 
+    /// Get the volume a trader can still trade in the current window (None if unlimited)
+    pub fn get_volume_allowance(env: Env, trader: Address) -> Option<u64> {
+        Self::volume_limit(&env)
+            .map(|limit| RateLimiter::remaining(&env, &symbol_short!("volume"), &trader, &limit))
+    }
+
     /// Get current contract version
     pub fn get_version(env: Env) -> u32 {
         let version_key = symbol_short!("ver");
@@ -208,84 +249,425 @@ impl UpgradeableTradingContract {
             })
     }
 
-    /// Pause the contract (admin only)
-    pub fn pause(env: Env, admin: Address) -> Result<(), TradeError> {
+    /// Set the candle intervals (in seconds) tracked for a pair (admin only)
+    pub fn set_candle_intervals(
+        env: Env,
+        admin: Address,
+        pair: Symbol,
+        intervals: soroban_sdk::Vec<u64>,
+    ) -> Result<(), TradeError> {
         admin.require_auth();
+        Self::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-        // Verify admin role
-        let roles_key = symbol_short!("roles");
-        let roles: soroban_sdk::Map<Address, GovernanceRole> = env
-            .storage()
-            .persistent()
-            .get(&roles_key)
-            .ok_or(TradeError::Unauthorized)?;
+        if intervals.is_empty() || intervals.len() > MAX_CANDLE_INTERVALS {
+            return Err(TradeError::InvalidInterval);
+        }
+        let mut seen = soroban_sdk::Vec::new(&env);
+        for interval in intervals.iter() {
+            if interval == 0 || seen.contains(interval) {
+                return Err(TradeError::InvalidInterval);
+            }
+            seen.push_back(interval);
+        }
 
-        let role = roles
-            .get(admin)
-            .ok_or(TradeError::Unauthorized)?;
+        MarketData::set_intervals(&env, &pair, &intervals);
 
-        if role != GovernanceRole::Admin {
-            return Err(TradeError::Unauthorized);
+        Ok(())
+    }
+
+    /// Get the candle intervals tracked for a pair
+    pub fn get_candle_intervals(env: Env, pair: Symbol) -> soroban_sdk::Vec<u64> {
+        MarketData::intervals(&env, &pair)
+    }
+
+    /// Get up to `limit` OHLCV candles, starting with the bucket containing `from`
+    pub fn get_candles(
+        env: Env,
+        pair: Symbol,
+        interval: u64,
+        from: u64,
+        limit: u32,
+    ) -> Result<soroban_sdk::Vec<Candle>, TradeError> {
+        if !MarketData::intervals(&env, &pair).contains(interval) {
+            return Err(TradeError::InvalidInterval);
         }
 
-        let paused_key = symbol_short!("pause");
-        env.storage().persistent().set(&paused_key, &true);
+        Ok(MarketData::candles(&env, &pair, interval, from, limit))
+    }
 
-        Ok(())
+    /// Get the time-weighted average price over the last `window` seconds
+    pub fn get_twap(env: Env, pair: Symbol, window: u64) -> Result<i128, TradeError> {
+        if window == 0 {
+            return Err(TradeError::InvalidWindow);
+        }
+
+        MarketData::twap(&env, &pair, window)
     }
 
-    /// Unpause the contract (admin only)
-    pub fn unpause(env: Env, admin: Address) -> Result<(), TradeError> {
+    /// Switch a pair to batch auctions with the given window, or back to continuous with 0 (admin only)
+    pub fn set_auction_mode(
+        env: Env,
+        admin: Address,
+        pair: Symbol,
+        window: u64,
+    ) -> Result<(), TradeError> {
         admin.require_auth();
+        Self::require_role(&env, &admin, GovernanceRole::Admin)?;
 
-        let roles_key = symbol_short!("roles");
-        let roles: soroban_sdk::Map<Address, GovernanceRole> = env
-            .storage()
-            .persistent()
-            .get(&roles_key)
-            .ok_or(TradeError::Unauthorized)?;
+        // Don't strand collected orders when leaving auction mode
+        if window == 0 && AuctionBook::current(&env, &pair).is_some() {
+            return Err(TradeError::AuctionPendingSettlement);
+        }
+
+        AuctionBook::set_window(&env, &pair, window);
+
+        Ok(())
+    }
+
+    /// Submit a limit order to the pair's current auction; returns the auction ID.
+    ///
+    /// The fee is collected when the order is placed, filled or not, so
+    /// settlement never calls out to trader-chosen tokens.
+    pub fn place_order(
+        env: Env,
+        trader: Address,
+        order: OrderRequest,
+        fee_token: Address,
+        fee_amount: i128,
+        fee_recipient: Address,
+    ) -> Result<u64, TradeError> {
+        trader.require_auth();
+        let OrderRequest {
+            pair,
+            amount,
+            limit_price,
+            is_buy,
+        } = order;
+
+        // Calls out to the caller-chosen fee token and the admin-set attestation registry
+        let _guard = ReentrancyGuard::enter(&env, symbol_short!("trade"))?;
+
+        if PauseManager::is_blocked(&env, Symbol::new(&env, "place_order"), Some(pair.clone())) {
+            return Err(TradeError::ContractPaused);
+        }
+
+        let window = AuctionBook::window(&env, &pair);
+        if window == 0 {
+            return Err(TradeError::AuctionNotFound);
+        }
+
+        if amount <= 0 || limit_price <= 0 {
+            return Err(TradeError::InvalidAmount);
+        }
+        if let Some(min_order) = ParamStore::get(&env, &AUCTION_MIN_ORDER_PARAM) {
+            if amount < min_order {
+                return Err(TradeError::OrderTooSmall);
+            }
+        }
+
+        Self::require_eligible(&env, &pair, &trader)?;
+
+        let now = env.ledger().timestamp();
+        let auction = match AuctionBook::current(&env, &pair) {
+            Some(auction) if now >= auction.end => {
+                return Err(TradeError::AuctionPendingSettlement)
+            }
+            Some(auction) => auction,
+            None => AuctionBook::open(&env, &pair, window),
+        };
+
+        let mut orders = AuctionBook::orders(&env, &pair, auction.id);
+        if orders.len() >= MAX_AUCTION_ORDERS {
+            return Err(TradeError::AuctionFull);
+        }
+        if AuctionBook::orders_by(&orders, &trader) >= MAX_ORDERS_PER_TRADER {
+            return Err(TradeError::TraderOrderLimit);
+        }
+
+        // Settlement multiplies amounts no larger than the book total, so keep
+        // total * total in range; an order that can't be settled isn't accepted
+        orders
+            .iter()
+            .try_fold(amount, |total, order| total.checked_add(order.amount))
+            .and_then(|total| total.checked_mul(total))
+            .ok_or(TradeError::ArithmeticOverflow)?;
+
+        // Fills never exceed the order, so spending the allowance here covers settlement
+        Self::spend_volume(&env, &trader, amount)?;
+
+        FeeManager::collect_fee(&env, &fee_token, &trader, &fee_recipient, fee_amount)?;
+
+        orders.push_back(AuctionOrder {
+            trader,
+            amount,
+            limit_price,
+            is_buy,
+            placed_at: now,
+        });
+        AuctionBook::set_orders(&env, &pair, auction.id, &orders);
+
+        Ok(auction.id)
+    }
+
+    /// Clear a closed auction at a single uniform price (anyone can call).
+    ///
+    /// Fills were already charged to each trader's volume allowance when
+    /// the order was placed.
+    pub fn settle_auction(env: Env, pair: Symbol) -> Result<AuctionResult, TradeError> {
+        if PauseManager::is_blocked(&env, Symbol::new(&env, "settle_auction"), Some(pair.clone())) {
+            return Err(TradeError::ContractPaused);
+        }
+
+        let auction = AuctionBook::current(&env, &pair).ok_or(TradeError::AuctionNotFound)?;
+        if env.ledger().timestamp() < auction.end {
+            return Err(TradeError::AuctionNotClosed);
+        }
 
-        let role = roles
-            .get(admin)
-            .ok_or(TradeError::Unauthorized)?;
+        let orders = AuctionBook::orders(&env, &pair, auction.id);
+        let (clearing_price, volume) = AuctionBook::clearing_price(&orders)?;
 
-        if role != GovernanceRole::Admin {
+        let mut fills = 0u32;
+        if volume > 0 {
+            let buy_fills = AuctionBook::allocate(&env, &orders, true, clearing_price, volume)?;
+            let sell_fills = AuctionBook::allocate(&env, &orders, false, clearing_price, volume)?;
+
+            for (index, order) in orders.iter().enumerate() {
+                let fill = if order.is_buy {
+                    buy_fills.get_unchecked(index as u32)
+                } else {
+                    sell_fills.get_unchecked(index as u32)
+                };
+                if fill > 0 {
+                    Self::store_trade(&env, order.trader, pair.clone(), fill, clearing_price, order.is_buy)?;
+                    fills += 1;
+                }
+            }
+
+            MarketData::record_trade(&env, &pair, clearing_price, volume)?;
+        }
+
+        AuctionBook::close(&env, &pair, &auction);
+
+        let result = AuctionResult {
+            pair,
+            auction_id: auction.id,
+            clearing_price,
+            volume,
+            fills,
+            settled_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("auction"),), result.clone());
+
+        Ok(result)
+    }
+
+    /// Get the pair's current auction round, if any
+    pub fn get_auction(env: Env, pair: Symbol) -> Option<Auction> {
+        AuctionBook::current(&env, &pair)
+    }
+
+    /// Get the orders collected for an auction round
+    pub fn get_auction_orders(
+        env: Env,
+        pair: Symbol,
+        auction_id: u64,
+    ) -> soroban_sdk::Vec<AuctionOrder> {
+        AuctionBook::orders(&env, &pair, auction_id)
+    }
+
+    /// Pause a scope for up to `MAX_PAUSE_DURATION` seconds (admin or guardian).
+    ///
+    /// A scope that is already paused can't be paused again; only an
+    /// `ExtendPause` proposal moves its expiry. Once a pause lapses, the scope
+    /// can't be paused directly for `PAUSE_COOLDOWN`; a governance `Pause` can.
+    pub fn pause(
+        env: Env,
+        caller: Address,
+        scope: PauseScope,
+        duration: u64,
+    ) -> Result<(), TradeError> {
+        caller.require_auth();
+
+        if !RoleManager::has_role(&env, &caller, &GovernanceRole::Admin)
+            && !RoleManager::has_role(&env, &caller, &GovernanceRole::Guardian)
+        {
             return Err(TradeError::Unauthorized);
         }
 
-        let paused_key = symbol_short!("pause");
-        env.storage().persistent().set(&paused_key, &false);
+        if duration == 0 || duration > MAX_PAUSE_DURATION {
+            return Err(TradeError::InvalidPauseDuration);
+        }
+
+        if PauseManager::is_paused(&env, &scope) {
+            return Err(TradeError::AlreadyPaused);
+        }
+
+        let now = env.ledger().timestamp();
+        if let Some(last) = PauseManager::last(&env, &scope) {
+            if now < last.expires_at.saturating_add(PAUSE_COOLDOWN) {
+                return Err(TradeError::PauseCooldown);
+            }
+        }
+        let state = PauseState {
+            paused_by: caller.clone(),
+            paused_at: now,
+            expires_at: now + duration,
+        };
+        PauseManager::set(&env, &scope, &state);
+
+        let event = PauseEvent {
+            scope,
+            caller,
+            expires_at: state.expires_at,
+            timestamp: now,
+        };
+        env.events().publish((symbol_short!("paused"),), event);
+
+        Ok(())
+    }
+
+    /// Lift the pause on a scope (admin only)
+    pub fn unpause(env: Env, admin: Address, scope: PauseScope) -> Result<(), TradeError> {
+        admin.require_auth();
+        Self::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+        PauseManager::clear(&env, &scope);
+
+        let now = env.ledger().timestamp();
+        let event = PauseEvent {
+            scope,
+            caller: admin,
+            expires_at: now,
+            timestamp: now,
+        };
+        env.events().publish((symbol_short!("unpause"),), event);
+
+        Ok(())
+    }
+
+    /// Get the active pause on a scope, if any
+    pub fn get_pause(env: Env, scope: PauseScope) -> Option<PauseState> {
+        PauseManager::get(&env, &scope)
+    }
+
+    /// Choose how traders are checked on a pair (admin only)
+    pub fn set_eligibility(
+        env: Env,
+        admin: Address,
+        pair: Symbol,
+        mode: EligibilityMode,
+    ) -> Result<(), TradeError> {
+        admin.require_auth();
+        Self::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+        Compliance::set_mode(&env, &pair, &mode);
 
         Ok(())
     }
 
+    /// Get the eligibility mode of a pair
+    pub fn get_eligibility(env: Env, pair: Symbol) -> EligibilityMode {
+        Compliance::get_mode(&env, &pair)
+    }
+
+    /// Add a batch of traders to a pair's allowlist (compliance role only)
+    pub fn add_to_allowlist(
+        env: Env,
+        officer: Address,
+        pair: Symbol,
+        traders: soroban_sdk::Vec<Address>,
+    ) -> Result<(), TradeError> {
+        officer.require_auth();
+        Self::require_role(&env, &officer, GovernanceRole::Compliance)?;
+
+        Compliance::update_allowlist(&env, &pair, &traders, true);
+
+        let event = AllowlistEvent {
+            pair,
+            traders,
+            updated_by: officer,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("allow_add"),), event);
+
+        Ok(())
+    }
+
+    /// Remove a batch of traders from a pair's allowlist (compliance role only)
+    pub fn remove_from_allowlist(
+        env: Env,
+        officer: Address,
+        pair: Symbol,
+        traders: soroban_sdk::Vec<Address>,
+    ) -> Result<(), TradeError> {
+        officer.require_auth();
+        Self::require_role(&env, &officer, GovernanceRole::Compliance)?;
+
+        Compliance::update_allowlist(&env, &pair, &traders, false);
+
+        let event = AllowlistEvent {
+            pair,
+            traders,
+            updated_by: officer,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("allow_rm"),), event);
+
+        Ok(())
+    }
+
+    /// Check whether a trader is on a pair's allowlist
+    pub fn is_allowlisted(env: Env, pair: Symbol, trader: Address) -> bool {
+        Compliance::is_allowlisted(&env, &pair, &trader)
+    }
+
     /// Propose an upgrade via governance
     pub fn propose_upgrade(
         env: Env,
         admin: Address,
         new_contract_hash: Symbol,
         description: Symbol,
-        approvers: soroban_sdk::Vec<Address>,
-        approval_threshold: u32,
-        timelock_delay: u64,
-    ) -> Result<u64, TradeError> {
+    ) -> Result<u64, GovernanceError> {
         admin.require_auth();
 
-        let proposal_result = GovernanceManager::propose_upgrade(
+        GovernanceManager::propose_upgrade(
             &env,
             admin,
             new_contract_hash,
             env.current_contract_address(),
             description,
-            approval_threshold,
-            approvers,
-            timelock_delay,
-        );
-
-        match proposal_result {
-            Ok(id) => Ok(id),
-            Err(_) => Err(TradeError::Unauthorized),
-        }
+        )
+    }
+
+    /// Propose rolling back the last executed upgrade (Admin); uses the short rollback timelock
+    pub fn propose_rollback(
+        env: Env,
+        admin: Address,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        admin.require_auth();
+
+        GovernanceManager::propose_rollback(&env, admin, description)
+    }
+
+    /// Get executed upgrades that can still be rolled back, oldest first
+    pub fn get_upgrade_history(env: Env) -> soroban_sdk::Vec<UpgradeRecord> {
+        UpgradeManager::history(&env)
+    }
+
+    /// Get the storage schema version
+    pub fn get_schema_version(env: Env) -> u32 {
+        UpgradeManager::schema_version(&env)
+    }
+
+    /// Get the registry entry for a trusted call target
+    pub fn get_trusted_target(env: Env, contract: Address) -> Option<TrustedTarget> {
+        TargetRegistry::get(&env, &contract)
+    }
+
+    /// Whether calls and fee tokens must be registered trusted targets
+    pub fn requires_trusted_targets(env: Env) -> bool {
+        TargetRegistry::is_required(&env)
     }
 
     /// Approve an upgrade proposal
@@ -293,11 +675,35 @@ impl UpgradeableTradingContract {
         env: Env,
         proposal_id: u64,
         approver: Address,
-    ) -> Result<(), TradeError> {
+    ) -> Result<(), GovernanceError> {
         approver.require_auth();
 
         GovernanceManager::approve_proposal(&env, proposal_id, approver)
-            .map_err(|_| TradeError::Unauthorized)
+    }
+
+    /// Record several approvals at once from off-chain ed25519 signatures (anyone can relay)
+    pub fn approve_with_signatures(
+        env: Env,
+        proposal_id: u64,
+        signatures: soroban_sdk::Vec<ApprovalSignature>,
+    ) -> Result<u32, GovernanceError> {
+        GovernanceManager::approve_with_signatures(&env, proposal_id, signatures)
+    }
+
+    /// Get the digest council members sign to approve a proposal
+    pub fn get_approval_digest(env: Env, proposal_id: u64) -> BytesN<32> {
+        GovernanceManager::approval_digest(&env, proposal_id)
+    }
+
+    /// Register the ed25519 key an approver signs off-chain approvals with
+    pub fn set_signing_key(
+        env: Env,
+        approver: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), GovernanceError> {
+        approver.require_auth();
+
+        GovernanceManager::set_signing_key(&env, &approver, &public_key)
     }
 
     /// Execute an approved upgrade proposal
@@ -305,29 +711,114 @@ impl UpgradeableTradingContract {
         env: Env,
         proposal_id: u64,
         executor: Address,
-    ) -> Result<(), TradeError> {
+    ) -> Result<(), GovernanceError> {
         executor.require_auth();
 
         GovernanceManager::execute_proposal(&env, proposal_id, executor)
-            .map_err(|_| TradeError::Unauthorized)
     }
 
     /// Get upgrade proposal details
-    pub fn get_upgrade_proposal(env: Env, proposal_id: u64) -> Result<UpgradeProposal, TradeError> {
+    pub fn get_upgrade_proposal(env: Env, proposal_id: u64) -> Result<UpgradeProposal, GovernanceError> {
         GovernanceManager::get_proposal(&env, proposal_id)
-            .map_err(|_| TradeError::Unauthorized)
     }
 
-    /// Reject an upgrade proposal
+    /// List proposals after `cursor`, optionally filtered by status
+    pub fn list_proposals(
+        env: Env,
+        status: Option<ProposalStatus>,
+        cursor: u64,
+        limit: u32,
+    ) -> ProposalPage {
+        GovernanceManager::list_proposals(&env, status, cursor, limit)
+    }
+
+    /// Move governance state from the legacy shared maps to per-proposal keys (admin only)
+    pub fn migrate_governance(env: Env, admin: Address) -> Result<u32, GovernanceError> {
+        admin.require_auth();
+
+        GovernanceManager::migrate_storage(&env, &admin)
+    }
+
+    /// Mark a proposal that missed its voting deadline or execution window as expired
+    pub fn expire_upgrade(env: Env, proposal_id: u64) -> Result<(), GovernanceError> {
+        GovernanceManager::expire_proposal(&env, proposal_id)
+    }
+
+    /// Vote to reject a proposal; it is rejected once the veto threshold is reached
     pub fn reject_upgrade(
         env: Env,
         proposal_id: u64,
         rejector: Address,
-    ) -> Result<(), TradeError> {
+    ) -> Result<(), GovernanceError> {
         rejector.require_auth();
 
         GovernanceManager::reject_proposal(&env, proposal_id, rejector)
-            .map_err(|_| TradeError::Unauthorized)
+    }
+
+    /// Withdraw an approval before the proposal's timelock ends
+    pub fn revoke_approval(
+        env: Env,
+        proposal_id: u64,
+        approver: Address,
+    ) -> Result<(), GovernanceError> {
+        approver.require_auth();
+
+        GovernanceManager::revoke_approval(&env, proposal_id, approver)
+    }
+
+    /// Hand an approver's vote to a stand-in until `until` (max 90 days)
+    pub fn delegate_approval(
+        env: Env,
+        delegator: Address,
+        delegate: Address,
+        until: u64,
+    ) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+
+        GovernanceManager::delegate_approval(&env, delegator, delegate, until)
+    }
+
+    /// End an approver's delegation early
+    pub fn revoke_delegation(env: Env, delegator: Address) -> Result<(), GovernanceError> {
+        delegator.require_auth();
+
+        GovernanceManager::revoke_delegation(&env, delegator)
+    }
+
+    /// Approve for the approver who delegated to `delegate`; counts for their seat
+    pub fn approve_as_delegate(
+        env: Env,
+        proposal_id: u64,
+        delegate: Address,
+        delegator: Address,
+    ) -> Result<(), GovernanceError> {
+        delegate.require_auth();
+
+        GovernanceManager::approve_as_delegate(&env, proposal_id, delegate, delegator)
+    }
+
+    /// Get an approver's active delegation
+    pub fn get_delegation(env: Env, delegator: Address) -> Option<ApprovalDelegation> {
+        GovernanceManager::get_delegation(&env, &delegator)
+    }
+
+    /// Get every approver's recorded vote on a proposal
+    pub fn get_council_votes(
+        env: Env,
+        proposal_id: u64,
+    ) -> Result<soroban_sdk::Vec<ApproverVote>, GovernanceError> {
+        GovernanceManager::get_council_votes(&env, proposal_id)
+    }
+
+    /// Veto an approved proposal before its timelock ends (Guardian, rate-limited)
+    pub fn veto_upgrade(
+        env: Env,
+        proposal_id: u64,
+        guardian: Address,
+    ) -> Result<(), GovernanceError> {
+        guardian.require_auth();
+
+        GovernanceManager::veto_proposal(&env, proposal_id, guardian)
     }
 
     /// Cancel an upgrade proposal (admin only)
@@ -335,13 +826,284 @@ impl UpgradeableTradingContract {
         env: Env,
         proposal_id: u64,
         admin: Address,
-    ) -> Result<(), TradeError> {
+    ) -> Result<(), GovernanceError> {
         admin.require_auth();
 
         GovernanceManager::cancel_proposal(&env, proposal_id, admin)
-            .map_err(|_| TradeError::Unauthorized)
+    }
+
+    /// Propose a governance action (parameter, role, council, pause, treasury, or contract call)
+    pub fn propose_action(
+        env: Env,
+        admin: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        admin.require_auth();
+
+        GovernanceManager::propose(&env, admin, action, description)
+    }
+
+    /// Propose several actions that execute in order, all or nothing
+    pub fn propose_actions(
+        env: Env,
+        admin: Address,
+        actions: soroban_sdk::Vec<ProposalAction>,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        admin.require_auth();
+
+        GovernanceManager::propose_batch(&env, admin, actions, description)
+    }
+
+    /// Propose an emergency pause or guardian rotation (Admin or Guardian).
+    ///
+    /// Needs unanimous council approval but can execute as soon as it is approved.
+    pub fn propose_emergency(
+        env: Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+
+        GovernanceManager::propose_emergency(&env, proposer, action, description)
+    }
+
+    /// Propose granting a role; applied by `execute_upgrade` after approval and timelock
+    pub fn grant_role(
+        env: Env,
+        proposer: Address,
+        member: Address,
+        role: GovernanceRole,
+    ) -> Result<u64, GovernanceError> {
+        Self::propose_role_change(&env, proposer, ProposalAction::GrantRole(member, role))
+    }
+
+    /// Propose revoking a role; applied by `execute_upgrade` after approval and timelock
+    pub fn revoke_role(
+        env: Env,
+        proposer: Address,
+        member: Address,
+        role: GovernanceRole,
+    ) -> Result<u64, GovernanceError> {
+        Self::propose_role_change(&env, proposer, ProposalAction::RevokeRole(member, role))
+    }
+
+    /// Get the approver council that decides new proposals
+    pub fn get_council(env: Env) -> Council {
+        CouncilManager::get(&env)
+    }
+
+    /// Get a governance-managed parameter
+    pub fn get_param(env: Env, key: Symbol) -> Option<i128> {
+        ParamStore::get(&env, &key)
+    }
+
+    /// Get every scheduled change to a parameter, oldest first
+    pub fn get_param_history(env: Env, key: Symbol) -> soroban_sdk::Vec<ParamChange> {
+        ParamStore::history(&env, &key)
+    }
+
+    /// List every address holding a role
+    pub fn get_role_members(env: Env, role: GovernanceRole) -> soroban_sdk::Vec<Address> {
+        RoleManager::members(&env, &role)
+    }
+
+    /// Check whether an address holds a role
+    pub fn has_role(env: Env, address: Address, role: GovernanceRole) -> bool {
+        RoleManager::has_role(&env, &address, &role)
+    }
+
+    /// Get the role whose holders may propose changes to `role`
+    pub fn get_role_admin(env: Env, role: GovernanceRole) -> GovernanceRole {
+        RoleManager::role_admin(&env, &role)
+    }
+
+    /// Enable token-weighted voting (admin only, once; later changes go through a proposal)
+    pub fn configure_token_voting(
+        env: Env,
+        admin: Address,
+        config: VotingConfig,
+    ) -> Result<(), GovernanceError> {
+        admin.require_auth();
+        GovernanceManager::require_role(&env, &admin, GovernanceRole::Admin)?;
+
+        if TokenVoting::get_config(&env).is_some() {
+            return Err(GovernanceError::Unauthorized);
+        }
+
+        TokenVoting::set_config(&env, &config)
+    }
+
+    /// Get the token voting configuration
+    pub fn get_voting_config(env: Env) -> Option<VotingConfig> {
+        TokenVoting::get_config(&env)
+    }
+
+    /// Open a token-holder vote on a governance action
+    pub fn propose_token_vote(
+        env: Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+
+        TokenVoting::propose(&env, proposer, action, description)
+    }
+
+    /// Vote on a token-holder proposal; returns the voting power counted
+    pub fn cast_vote(
+        env: Env,
+        proposal_id: u64,
+        voter: Address,
+        choice: VoteChoice,
+        reason: Symbol,
+    ) -> Result<i128, GovernanceError> {
+        voter.require_auth();
+
+        TokenVoting::cast_vote(&env, proposal_id, voter, choice, reason)
+    }
+
+    /// Close a token-holder vote after its voting period
+    pub fn finalize_vote(env: Env, proposal_id: u64) -> Result<ProposalStatus, GovernanceError> {
+        TokenVoting::finalize(&env, proposal_id)
+    }
+
+    /// Get the running tally of a token-holder vote
+    pub fn get_vote_tally(env: Env, proposal_id: u64) -> VoteTally {
+        TokenVoting::get_tally(&env, proposal_id)
+    }
+
+    /// Get the quorum and pass threshold a token-holder vote is decided by
+    pub fn get_vote_terms(env: Env, proposal_id: u64) -> Result<VoteTerms, GovernanceError> {
+        TokenVoting::get_terms(&env, proposal_id)
+    }
+
+    /// Get a voter's ballot on a token-holder vote
+    pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<VoteRecord> {
+        TokenVoting::get_vote(&env, proposal_id, voter)
+    }
+
+    /// Internal helper: create the governance proposal backing a role change
+    fn propose_role_change(
+        env: &Env,
+        proposer: Address,
+        action: ProposalAction,
+    ) -> Result<u64, GovernanceError> {
+        proposer.require_auth();
+
+        // Fail early; the same checks run again when the proposal executes
+        let (member, role, grant, description) = match &action {
+            ProposalAction::GrantRole(member, role) => (member, role, true, symbol_short!("grant")),
+            ProposalAction::RevokeRole(member, role) => (member, role, false, symbol_short!("revoke")),
+            _ => return Err(GovernanceError::InvalidAction),
+        };
+        GovernanceManager::check_role_change(env, member, role, grant)?;
+
+        GovernanceManager::propose(env, proposer, action, description)
+    }
+
+    /// Internal helper: define the bounded parameters this contract reads
+    fn define_params(env: &Env) -> Result<(), GovernanceError> {
+        if ParamStore::spec(env, &VOLUME_WINDOW_PARAM).is_none() {
+            let window = ParamSpec {
+                min: 1,
+                max: MAX_WINDOW as i128,
+                change_delay: 0,
+            };
+            ParamStore::define(env, &VOLUME_WINDOW_PARAM, &window, DEFAULT_VOLUME_WINDOW as i128)?;
+        }
+        Ok(())
+    }
+
+    /// Internal helper: per-trader volume limit from the parameter store
+    fn volume_limit(env: &Env) -> Option<RateLimit> {
+        let capacity = ParamStore::get_u64(env, &VOLUME_CAP_PARAM)?;
+        // Contracts initialized before `vol_win` was defined here may hold any value
+        let window = ParamStore::get_u64(env, &VOLUME_WINDOW_PARAM)
+            .unwrap_or(DEFAULT_VOLUME_WINDOW)
+            .min(MAX_WINDOW);
+        Some(RateLimit { capacity, window })
+    }
+
+    /// Internal helper: spend a trader's volume allowance, if governance set one
+    fn spend_volume(env: &Env, trader: &Address, amount: i128) -> Result<(), TradeError> {
+        if let Some(limit) = Self::volume_limit(env) {
+            let volume = u64::try_from(amount).map_err(|_| TradeError::InvalidAmount)?;
+            RateLimiter::consume(env, &symbol_short!("volume"), trader, &limit, volume)?;
+        }
+        Ok(())
+    }
+
+    /// Internal helper: verify a trader passes the pair's eligibility check
+    fn require_eligible(env: &Env, pair: &Symbol, trader: &Address) -> Result<(), TradeError> {
+        match Compliance::check(env, pair, trader) {
+            Eligibility::Eligible => Ok(()),
+            Eligibility::NotEligible => Err(TradeError::TraderNotEligible),
+            Eligibility::Unavailable => Err(TradeError::AttestationUnavailable),
+        }
+    }
+
+    /// Internal helper: append a trade record and update stats
+    fn store_trade(
+        env: &Env,
+        trader: Address,
+        pair: Symbol,
+        amount: i128,
+        price: i128,
+        is_buy: bool,
+    ) -> Result<u64, TradeError> {
+        let stats_key = symbol_short!("stats");
+        let mut stats: TradeStats = env
+            .storage()
+            .persistent()
+            .get(&stats_key)
+            .unwrap_or(TradeStats {
+                total_trades: 0,
+                total_volume: 0,
+                last_trade_id: 0,
+            });
+
+        let trade_id = stats.last_trade_id + 1;
+        let trade = Trade {
+            id: trade_id,
+            trader,
+            pair,
+            amount,
+            price,
+            timestamp: env.ledger().timestamp(),
+            is_buy,
+        };
+
+        // Update stats
+        stats.total_trades += 1;
+        stats.total_volume = stats.total_volume.checked_add(amount).ok_or(TradeError::ArithmeticOverflow)?;
+        stats.last_trade_id = trade_id;
+
+        // Store trade
+        let trades_key = symbol_short!("trades");
+        let mut trades: soroban_sdk::Vec<Trade> = env
+            .storage()
+            .persistent()
+            .get(&trades_key)
+            .unwrap_or_else(|| soroban_sdk::Vec::new(env));
+
+        trades.push_back(trade);
+
+        // Update persistent storage
+        env.storage().persistent().set(&trades_key, &trades);
+        env.storage().persistent().set(&stats_key, &stats);
+
+        Ok(trade_id)
+    }
+
+    /// Internal helper: verify an address holds the given role
+    fn require_role(env: &Env, address: &Address, role: GovernanceRole) -> Result<(), TradeError> {
+        RoleManager::require_role(env, address, &role).map_err(|_| TradeError::Unauthorized)
     }
 }
 
-// #[cfg(test)]
-// mod test; // Removed failing tests
+#[cfg(test)]
+mod test;
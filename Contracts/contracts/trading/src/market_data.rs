@@ -0,0 +1,239 @@
+use soroban_sdk::{contracttype, symbol_short, vec, Env, Symbol, Vec};
+
+use crate::TradeError;
+
+/// Most candle intervals a pair can track
+pub const MAX_CANDLE_INTERVALS: u32 = 4;
+
+/// OHLCV bucket for one interval on one pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Candle {
+    pub open_time: u64,
+    pub open: i128,
+    pub high: i128,
+    pub low: i128,
+    pub close: i128,
+    pub volume: i128,
+    pub trades: u32,
+}
+
+/// Cumulative price snapshot, taken at most once per ledger timestamp
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceObservation {
+    pub timestamp: u64,
+    pub cumulative: i128,     // Sum of price * seconds up to `timestamp`
+    pub price: i128,          // Price in effect from `timestamp` onwards
+}
+
+pub struct MarketData;
+
+impl MarketData {
+    /// Candle intervals tracked for a pair (defaults to 1m/1h/1d)
+    pub fn intervals(env: &Env, pair: &Symbol) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("cintv"), pair.clone()))
+            .unwrap_or_else(|| vec![env, 60u64, 3_600u64, 86_400u64])
+    }
+
+    /// Set the candle intervals tracked for a pair
+    pub fn set_intervals(env: &Env, pair: &Symbol, intervals: &Vec<u64>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("cintv"), pair.clone()), intervals);
+    }
+
+    /// Fold a trade into every candle interval and the price accumulator
+    pub fn record_trade(env: &Env, pair: &Symbol, price: i128, amount: i128) -> Result<(), TradeError> {
+        let now = env.ledger().timestamp();
+
+        for interval in Self::intervals(env, pair).iter() {
+            Self::update_candle(env, pair, interval, now, price, amount)?;
+        }
+
+        Self::update_accumulator(env, pair, now, price)
+    }
+
+    /// Candles for an interval, starting with the bucket containing `from`
+    pub fn candles(env: &Env, pair: &Symbol, interval: u64, from: u64, limit: u32) -> Vec<Candle> {
+        let mut candles = Vec::new(env);
+        let count = Self::candle_count(env, pair, interval);
+        let bucket = from - from % interval;
+
+        // First candle whose bucket is at or after `from`'s bucket
+        let mut lo = 0u32;
+        let mut hi = count;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if Self::candle_at(env, pair, interval, mid).open_time < bucket {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let end = count.min(lo.saturating_add(limit));
+        for index in lo..end {
+            candles.push_back(Self::candle_at(env, pair, interval, index));
+        }
+
+        candles
+    }
+
+    /// Time-weighted average price over the last `window` seconds
+    pub fn twap(env: &Env, pair: &Symbol, window: u64) -> Result<i128, TradeError> {
+        let now = env.ledger().timestamp();
+        let start = now.checked_sub(window).ok_or(TradeError::InsufficientHistory)?;
+        let count = Self::observation_count(env, pair);
+        if count == 0 {
+            return Err(TradeError::InsufficientHistory);
+        }
+
+        // Last observation at or before the start of the window
+        let mut lo = 0u32;
+        let mut hi = count;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if Self::observation_at(env, pair, mid).timestamp <= start {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        if lo == 0 {
+            return Err(TradeError::InsufficientHistory);
+        }
+
+        let first = Self::observation_at(env, pair, lo - 1);
+        let last = Self::observation_at(env, pair, count - 1);
+
+        let cumulative_start = Self::accumulate(&first, start)?;
+        let cumulative_now = Self::accumulate(&last, now)?;
+
+        Ok((cumulative_now - cumulative_start) / window as i128)
+    }
+
+    /// Cumulative price at `at`, carrying the observation's price forward
+    fn accumulate(observation: &PriceObservation, at: u64) -> Result<i128, TradeError> {
+        observation
+            .price
+            .checked_mul((at - observation.timestamp) as i128)
+            .and_then(|elapsed| observation.cumulative.checked_add(elapsed))
+            .ok_or(TradeError::ArithmeticOverflow)
+    }
+
+    fn update_candle(
+        env: &Env,
+        pair: &Symbol,
+        interval: u64,
+        now: u64,
+        price: i128,
+        amount: i128,
+    ) -> Result<(), TradeError> {
+        let open_time = now - now % interval;
+        let count = Self::candle_count(env, pair, interval);
+
+        if count > 0 {
+            let mut candle = Self::candle_at(env, pair, interval, count - 1);
+            if candle.open_time == open_time {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.volume = candle.volume.checked_add(amount).ok_or(TradeError::ArithmeticOverflow)?;
+                candle.trades += 1;
+                Self::set_candle(env, pair, interval, count - 1, &candle);
+                return Ok(());
+            }
+        }
+
+        let candle = Candle {
+            open_time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: amount,
+            trades: 1,
+        };
+        Self::set_candle(env, pair, interval, count, &candle);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ccnt"), pair.clone(), interval), &(count + 1));
+
+        Ok(())
+    }
+
+    fn update_accumulator(env: &Env, pair: &Symbol, now: u64, price: i128) -> Result<(), TradeError> {
+        let count = Self::observation_count(env, pair);
+
+        let observation = if count == 0 {
+            PriceObservation {
+                timestamp: now,
+                cumulative: 0,
+                price,
+            }
+        } else {
+            let last = Self::observation_at(env, pair, count - 1);
+            if last.timestamp == now {
+                // Same ledger time: the latest trade sets the price going forward
+                let updated = PriceObservation { price, ..last };
+                Self::set_observation(env, pair, count - 1, &updated);
+                return Ok(());
+            }
+            PriceObservation {
+                timestamp: now,
+                cumulative: Self::accumulate(&last, now)?,
+                price,
+            }
+        };
+
+        Self::set_observation(env, pair, count, &observation);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ocnt"), pair.clone()), &(count + 1));
+
+        Ok(())
+    }
+
+    fn candle_count(env: &Env, pair: &Symbol, interval: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ccnt"), pair.clone(), interval))
+            .unwrap_or(0)
+    }
+
+    fn candle_at(env: &Env, pair: &Symbol, interval: u64, index: u32) -> Candle {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("candle"), pair.clone(), interval, index))
+            .unwrap()
+    }
+
+    fn set_candle(env: &Env, pair: &Symbol, interval: u64, index: u32, candle: &Candle) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("candle"), pair.clone(), interval, index), candle);
+    }
+
+    fn observation_count(env: &Env, pair: &Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ocnt"), pair.clone()))
+            .unwrap_or(0)
+    }
+
+    fn observation_at(env: &Env, pair: &Symbol, index: u32) -> PriceObservation {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("obs"), pair.clone(), index))
+            .unwrap()
+    }
+
+    fn set_observation(env: &Env, pair: &Symbol, index: u32, observation: &PriceObservation) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("obs"), pair.clone(), index), observation);
+    }
+}
@@ -1,8 +1,17 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{Env, testutils::Address as _, Vec, symbol_short};
-use shared::governance::ProposalStatus;
+extern crate std;
+use soroban_sdk::{
+    token, BytesN, Env, IntoVal, testutils::Address as _, testutils::Events as _, testutils::Ledger, Vec, symbol_short,
+};
+use shared::governance::{
+    ActionFailedEvent, ActionOutcome, ApproverVote, CouncilVote, LegacyUpgradeProposal, ProposalStatus, DEFAULT_CONFIG_TIMELOCK, DEFAULT_MIN_TIMELOCK,
+    DEFAULT_OPS_TIMELOCK, DEFAULT_ROLES_TIMELOCK, DEFAULT_UPGRADE_TIMELOCK, EXECUTION_WINDOW, MAX_TIMELOCK, MIN_TIMELOCK_PARAM,
+    TIMELOCK_CHANGE_DELAY, TIMELOCK_OPS_PARAM, VOTING_PERIOD,
+};
+use shared::params::ParamSpec;
+use shared::upgrade::PreviousHash;
 
 // We need to import the social rewards contract for testing
 // In a workspace, we can register the contract by its WASM, but here we can just register the struct if it's available.
@@ -16,7 +25,7 @@ pub struct MockRewardContract;
 
 #[contractimpl]
 impl MockRewardContract {
-    pub fn add_reward(env: Env, user: Address, amount: i128) {
+    pub fn add_reward(_env: Env, _user: Address, amount: i128) {
         if amount <= 0 {
             panic!("Invalid reward amount");
         }
@@ -27,97 +36,42 @@ impl MockRewardContract {
 #[test]
 fn test_contract_initialization() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver1 = Address::random(&env);
-    let approver2 = Address::random(&env);
-    let executor = Address::random(&env);
+    env.mock_all_auths();
 
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver1.clone());
-    approvers.push_back(approver2.clone());
+    let contract_id = env.register_contract(None, UpgradeableTradingContract);
+    let client = UpgradeableTradingContractClient::new(&env, &contract_id);
 
-    let result = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
+    let admin = Address::generate(&env);
+    let approvers = soroban_sdk::vec![&env, Address::generate(&env), Address::generate(&env)];
+    let executor = Address::generate(&env);
 
-    assert!(result.is_ok());
+    client.init(&admin, &approvers, &executor);
 
     // Verify version is set
-    let version = UpgradeableTradingContract::get_version(env);
-    assert_eq!(version, 1);
+    assert_eq!(client.get_version(), 1);
 }
 
 #[test]
 fn test_contract_cannot_be_initialized_twice() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver);
-
-    // First initialization should succeed
-    let result1 = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
-    assert!(result1.is_ok());
+    let (client, admin, approver, executor) = setup_governance(&env);
 
     // Second initialization should fail
-    let result2 = UpgradeableTradingContract::init(env, admin, approvers, executor);
-    assert!(result2.is_err());
+    let approvers = soroban_sdk::vec![&env, approver];
+    assert_eq!(client.try_init(&admin, &approvers, &executor), Err(Ok(GovernanceError::Unauthorized)));
 }
 
 #[test]
 fn test_upgrade_proposal_creation() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
+    let (client, admin, _approver, _executor) = setup_governance(&env);
 
     // Propose an upgrade
-    let new_hash = symbol_short!("v2hash");
-    let description = symbol_short!("Upgrade");
-    let result = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        new_hash,
-        description,
-        approvers.clone(),
-        1,
-        3600, // 1 hour timelock
-    );
-
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 1); // First proposal ID
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    assert_eq!(proposal_id, 1); // First proposal ID
 
     // Get proposal details
-    let proposal = UpgradeableTradingContract::get_upgrade_proposal(env, 1);
-    assert!(proposal.is_ok());
-    let prop = proposal.unwrap();
+    let prop = client.get_upgrade_proposal(&1);
     assert_eq!(prop.id, 1);
     assert_eq!(prop.approvals_count, 0);
     assert_eq!(prop.status, ProposalStatus::Pending);
@@ -126,52 +80,30 @@ fn test_upgrade_proposal_creation() {
 #[test]
 fn test_upgrade_proposal_approval_flow() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver1 = Address::random(&env);
-    let approver2 = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver1.clone());
-    approvers.push_back(approver2.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
-
-    // Propose an upgrade with 2 approvals required
-    let new_hash = symbol_short!("v2hash");
-    let description = symbol_short!("Upgrade");
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        new_hash,
-        description,
-        approvers.clone(),
-        2, // Need 2 approvals
-        3600,
-    )
-    .unwrap();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
 
-    // First approval
-    let result1 = UpgradeableTradingContract::approve_upgrade(env.clone(), proposal_id, approver1.clone());
-    assert!(result1.is_ok());
+    let contract_id = env.register_contract(None, UpgradeableTradingContract);
+    let client = UpgradeableTradingContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let approver1 = Address::generate(&env);
+    let approver2 = Address::generate(&env);
+    let executor = Address::generate(&env);
+    client.init(&admin, &soroban_sdk::vec![&env, approver1.clone(), approver2.clone()], &executor);
 
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env.clone(), proposal_id).unwrap();
+    // A 2-member council needs both approvals
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+
+    // First approval
+    client.approve_upgrade(&proposal_id, &approver1);
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.approvals_count, 1);
     assert_eq!(prop.status, ProposalStatus::Pending); // Still pending, need one more
 
     // Second approval
-    let result2 = UpgradeableTradingContract::approve_upgrade(env.clone(), proposal_id, approver2.clone());
-    assert!(result2.is_ok());
-
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env.clone(), proposal_id).unwrap();
+    client.approve_upgrade(&proposal_id, &approver2);
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.approvals_count, 2);
     assert_eq!(prop.status, ProposalStatus::Approved); // Now approved!
 }
@@ -179,59 +111,39 @@ fn test_upgrade_proposal_approval_flow() {
 #[test]
 fn test_upgrade_timelock_enforcement() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
+    let (client, admin, approver, executor) = setup_governance(&env);
 
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
+    // Upgrades wait out the upgrade-class timelock parameter (24 hours by default)
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.timelock, DEFAULT_UPGRADE_TIMELOCK);
+    assert_eq!(prop.execution_time, 0); // The clock hasn't started yet
 
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
-
-    // Propose an upgrade with 4-hour timelock
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        symbol_short!("v2hash"),
-        symbol_short!("Upgrade"),
-        approvers,
-        1,
-        14400, // 4 hours = 14400 seconds
-    )
-    .unwrap();
-
-    // Approve the proposal
-    let _ = UpgradeableTradingContract::approve_upgrade(env.clone(), proposal_id, approver);
+    // Approve the proposal; the timelock runs from here
+    client.approve_upgrade(&proposal_id, &approver);
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.execution_time, 1000 + DEFAULT_UPGRADE_TIMELOCK);
 
     // Try to execute immediately (should fail)
-    let execute_result = UpgradeableTradingContract::execute_upgrade(
-        env.clone(),
-        proposal_id,
-        executor.clone(),
+    assert_eq!(
+        client.try_execute_upgrade(&proposal_id, &executor),
+        Err(Ok(GovernanceError::TimelockNotExpired))
+    );
+
+    // Still locked one second before the end
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time - 1);
+    assert_eq!(
+        client.try_execute_upgrade(&proposal_id, &executor),
+        Err(Ok(GovernanceError::TimelockNotExpired))
     );
-    assert!(execute_result.is_err()); // Should fail - timelock not expired
 
-    // Advance time to after timelock
-    env.ledger().set_timestamp(1000 + 14401); // Past the 4-hour mark
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time);
 
     // Now execution should succeed
-    let execute_result = UpgradeableTradingContract::execute_upgrade(
-        env.clone(),
-        proposal_id,
-        executor,
-    );
-    assert!(execute_result.is_ok());
+    client.execute_upgrade(&proposal_id, &executor);
 
     // Verify proposal is marked as executed
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env, proposal_id).unwrap();
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.status, ProposalStatus::Executed);
     assert!(prop.executed);
 }
@@ -239,134 +151,54 @@ fn test_upgrade_timelock_enforcement() {
 #[test]
 fn test_upgrade_rejection_flow() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
+    let (client, admin, approver, _executor) = setup_governance(&env);
 
     // Propose an upgrade
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        symbol_short!("v2hash"),
-        symbol_short!("Upgrade"),
-        approvers,
-        1,
-        3600,
-    )
-    .unwrap();
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
 
     // Reject the proposal
-    let result = UpgradeableTradingContract::reject_upgrade(env.clone(), proposal_id, approver);
-    assert!(result.is_ok());
+    client.reject_upgrade(&proposal_id, &approver);
 
     // Verify status is rejected
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env, proposal_id).unwrap();
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.status, ProposalStatus::Rejected);
 }
 
 #[test]
 fn test_upgrade_cancellation_by_admin() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
+    let (client, admin, _approver, _executor) = setup_governance(&env);
 
     // Propose an upgrade
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        symbol_short!("v2hash"),
-        symbol_short!("Upgrade"),
-        approvers,
-        1,
-        3600,
-    )
-    .unwrap();
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
 
     // Admin can cancel at any time
-    let result = UpgradeableTradingContract::cancel_upgrade(env.clone(), proposal_id, admin);
-    assert!(result.is_ok());
+    client.cancel_upgrade(&proposal_id, &admin);
 
     // Verify status is cancelled
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env, proposal_id).unwrap();
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.status, ProposalStatus::Cancelled);
 }
 
 #[test]
 fn test_multi_sig_protection() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver1 = Address::random(&env);
-    let approver2 = Address::random(&env);
-    let approver3 = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver1.clone());
-    approvers.push_back(approver2.clone());
-    approvers.push_back(approver3.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers.clone(),
-        executor.clone(),
-    );
-
-    // Propose with 2 of 3 multi-sig requirement
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        symbol_short!("v2hash"),
-        symbol_short!("Upgrade"),
-        approvers,
-        2, // 2 of 3 required
-        3600,
-    )
-    .unwrap();
-
-    // Get initial proposal
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env.clone(), proposal_id).unwrap();
+    let (client, admin, members, _executor) = setup_council(&env);
+
+    // A 3-member council defaults to a 2 of 3 majority
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.approval_threshold, 2);
 
     // First approver approves
-    let _ = UpgradeableTradingContract::approve_upgrade(env.clone(), proposal_id, approver1);
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env.clone(), proposal_id).unwrap();
+    client.approve_upgrade(&proposal_id, &members[0]);
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.approvals_count, 1);
     assert_eq!(prop.status, ProposalStatus::Pending); // Not enough yet
 
     // Second approver approves
-    let _ = UpgradeableTradingContract::approve_upgrade(env.clone(), proposal_id, approver2);
-    let prop = UpgradeableTradingContract::get_upgrade_proposal(env.clone(), proposal_id).unwrap();
+    client.approve_upgrade(&proposal_id, &members[1]);
+    let prop = client.get_upgrade_proposal(&proposal_id);
     assert_eq!(prop.approvals_count, 2);
     assert_eq!(prop.status, ProposalStatus::Approved); // Now approved!
 
@@ -377,62 +209,26 @@ fn test_multi_sig_protection() {
 #[test]
 fn test_duplicate_approval_prevention() {
     let env = Env::default();
-    env.ledger().set_timestamp(1000);
-
-    let admin = Address::random(&env);
-    let approver = Address::random(&env);
-    let executor = Address::random(&env);
-
-    let mut approvers = Vec::new(&env);
-    approvers.push_back(approver.clone());
-
-    // Initialize contract
-    let _ = UpgradeableTradingContract::init(
-        env.clone(),
-        admin.clone(),
-        approvers,
-        executor,
-    );
+    let (client, admin, members, _executor) = setup_council(&env);
 
     // Propose an upgrade
-    let proposal_id = UpgradeableTradingContract::propose_upgrade(
-        env.clone(),
-        admin.clone(),
-        symbol_short!("v2hash"),
-        symbol_short!("Upgrade"),
-        vec![approver.clone()],
-        1,
-        3600,
-    )
-    .unwrap();
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
 
     // First approval should succeed
-    let result1 = UpgradeableTradingContract::approve_upgrade(
-        env.clone(),
-        proposal_id,
-        approver.clone(),
-    );
-    assert!(result1.is_ok());
+    client.approve_upgrade(&proposal_id, &members[0]);
 
     // Second approval from same address should fail
-    let result2 = UpgradeableTradingContract::approve_upgrade(
-        env,
-        proposal_id,
-        approver,
+    assert_eq!(
+        client.try_approve_upgrade(&proposal_id, &members[0]),
+        Err(Ok(GovernanceError::DuplicateApproval)) // Cannot approve twice
     );
-    assert!(result2.is_err()); // Cannot approve twice
 }
 
 #[test]
-fn test_trade_and_reward_success() {
+fn test_trade_collects_fee() {
     let env = Env::default();
-    env.mock_all_auths();
+    let (client, _admin, _approver, _executor) = setup_governance(&env);
 
-    let trading_id = env.register_contract(None, TradingContract);
-    let trading_client = TradingContractClient::new(&env, &trading_id);
-
-    let reward_id = env.register_contract(None, MockRewardContract);
-    
     // Setup Tokens
     let issuer = Address::generate(&env);
     let token_id = env.register_stellar_asset_contract(issuer);
@@ -445,17 +241,8 @@ fn test_trade_and_reward_success() {
 
     token_admin.mint(&trader, &1000);
 
-    // Run trade_and_reward
-    let res = trading_client.trade_and_reward(
-        &trader, 
-        &token_id, 
-        &fee, 
-        &recipient, 
-        &reward_id, 
-        &50 // valid reward
-    );
-
-    assert!(res.is_ok());
+    let trade_id = client.trade(&trader, &symbol_short!("XLMUSDC"), &10, &100, &true, &token_id, &fee, &recipient);
+    assert_eq!(trade_id, 1);
 
     // Verify fee was paid
     assert_eq!(token_client.balance(&trader), 900);
@@ -463,15 +250,10 @@ fn test_trade_and_reward_success() {
 }
 
 #[test]
-fn test_trade_and_reward_atomic_rollback() {
+fn test_failed_trade_leaves_balances_untouched() {
     let env = Env::default();
-    env.mock_all_auths();
+    let (client, _admin, _approver, _executor) = setup_governance(&env);
 
-    let trading_id = env.register_contract(None, TradingContract);
-    let trading_client = TradingContractClient::new(&env, &trading_id);
-
-    let reward_id = env.register_contract(None, MockRewardContract);
-    
     // Setup Tokens
     let issuer = Address::generate(&env);
     let token_id = env.register_stellar_asset_contract(issuer);
@@ -480,42 +262,1927 @@ fn test_trade_and_reward_atomic_rollback() {
 
     let trader = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let fee = 100;
 
     token_admin.mint(&trader, &1000);
 
-    // Run trade_and_reward with INVALID reward amount (0)
-    // This will cause MockRewardContract to panic.
-    // TradingContract::trade_and_reward uses safe_invoke, which catches the panic
-    // and returns SafeCallErrors::CALL_FAILED (mapped to u32).
-    // The TradingContract then returns Err.
-    // The ENV should revert all changes, including the fee payment.
-    
-    // Note: We use try_trade_and_reward to inspect the error result
-    let res = trading_client.try_trade_and_reward(
-        &trader, 
-        &token_id, 
-        &fee, 
-        &recipient, 
-        &reward_id, 
-        &0 // Invalid reward amount -> panic
-    );
-
-    // The result should be an Err
-    assert!(res.is_err());
-    
-    // Check error code
-    match res {
-        Err(Ok(code)) => {
-             // We expect CALL_FAILED (2001)
-             assert_eq!(code, SafeCallErrors::CALL_FAILED);
-        },
-        _ => panic!("Expected contract error code"),
-    }
+    // Fee larger than the balance
+    let res = client.try_trade(&trader, &symbol_short!("XLMUSDC"), &10, &100, &true, &token_id, &5000, &recipient);
+    assert_eq!(res, Err(Ok(TradeError::InsufficientBalance)));
 
-    // CRITICAL: Verify ATOMICITY
-    // The fee transfer (which happened before the cross-call) must be rolled back.
-    // Trader balance should still be 1000.
+    // Nothing was recorded or paid
+    assert_eq!(client.get_stats().total_trades, 0);
     assert_eq!(token_client.balance(&trader), 1000);
     assert_eq!(token_client.balance(&recipient), 0);
 }
+
+/// Fee token that tries to trade again from inside `transfer()`
+#[contract]
+pub struct ReentrantToken;
+
+#[contractimpl]
+impl ReentrantToken {
+    pub fn set_target(env: Env, target: Address, trader: Address) {
+        env.storage().instance().set(&symbol_short!("target"), &target);
+        env.storage().instance().set(&symbol_short!("trader"), &trader);
+    }
+
+    pub fn balance(_env: Env, _id: Address) -> i128 {
+        i128::MAX
+    }
+
+    pub fn transfer(env: Env, _from: Address, _to: Address, _amount: i128) {
+        let target: Address = env.storage().instance().get(&symbol_short!("target")).unwrap();
+        let trader: Address = env.storage().instance().get(&symbol_short!("trader")).unwrap();
+        let client = UpgradeableTradingContractClient::new(&env, &target);
+        let reentered = client.try_trade(
+            &trader,
+            &symbol_short!("XLMUSDC"),
+            &1,
+            &1,
+            &true,
+            &env.current_contract_address(),
+            &0,
+            &trader,
+        );
+        env.storage().instance().set(&symbol_short!("reentered"), &reentered.is_ok());
+    }
+
+    pub fn reentered(env: Env) -> bool {
+        env.storage().instance().get(&symbol_short!("reentered")).unwrap_or(false)
+    }
+}
+
+fn setup_governance(
+    env: &Env,
+) -> (UpgradeableTradingContractClient<'_>, Address, Address, Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let contract_id = env.register_contract(None, UpgradeableTradingContract);
+    let client = UpgradeableTradingContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let approver = Address::generate(env);
+    let executor = Address::generate(env);
+
+    let mut approvers = Vec::new(env);
+    approvers.push_back(approver.clone());
+    client.init(&admin, &approvers, &executor);
+
+    (client, admin, approver, executor)
+}
+
+/// Move the ledger clock to the end of a proposal's timelock
+fn pass_timelock(env: &Env, client: &UpgradeableTradingContractClient, proposal_id: u64) {
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    env.ledger().with_mut(|li| li.timestamp = li.timestamp.max(prop.execution_time));
+}
+
+#[test]
+fn test_pending_proposal_expires_after_voting_deadline() {
+    let env = Env::default();
+    let (client, admin, approver, _executor) = setup_governance(&env);
+
+    let proposal_id = client.propose_upgrade(
+        &admin,
+        &symbol_short!("v2hash"),
+        &symbol_short!("Upgrade"),
+    );
+
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.voting_deadline, 1000 + VOTING_PERIOD);
+
+    // Last second of the voting period: still pending
+    env.ledger().with_mut(|li| li.timestamp = 1000 + VOTING_PERIOD);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Pending);
+    assert_eq!(client.try_expire_upgrade(&proposal_id), Err(Ok(GovernanceError::InvalidProposal)));
+
+    // Past the deadline: queries report Expired and approvals are refused
+    env.ledger().with_mut(|li| li.timestamp = 1000 + VOTING_PERIOD + 1);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Expired);
+    assert_eq!(client.try_approve_upgrade(&proposal_id, &approver), Err(Ok(GovernanceError::ProposalExpired)));
+
+    // Anyone can persist the transition
+    client.expire_upgrade(&proposal_id);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Expired);
+    assert_eq!(client.try_expire_upgrade(&proposal_id), Err(Ok(GovernanceError::InvalidProposal)));
+}
+
+#[test]
+fn test_approved_proposal_expires_after_execution_window() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+
+    let proposal_id = client.propose_upgrade(
+        &admin,
+        &symbol_short!("v2hash"),
+        &symbol_short!("Upgrade"),
+    );
+    client.approve_upgrade(&proposal_id, &approver);
+
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.status, ProposalStatus::Approved);
+    assert_eq!(prop.execution_window, EXECUTION_WINDOW);
+
+    // Past execution_time + execution_window the proposal can no longer run
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time + EXECUTION_WINDOW + 1);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Expired);
+    assert_eq!(client.try_execute_upgrade(&proposal_id, &executor), Err(Ok(GovernanceError::ProposalExpired)));
+
+    client.expire_upgrade(&proposal_id);
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.status, ProposalStatus::Expired);
+    assert!(!prop.executed);
+}
+
+#[test]
+fn test_approved_proposal_executes_at_end_of_window() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+
+    let proposal_id = client.propose_upgrade(
+        &admin,
+        &symbol_short!("v2hash"),
+        &symbol_short!("Upgrade"),
+    );
+
+    // Approve on the last second of the voting period
+    env.ledger().with_mut(|li| li.timestamp = 1000 + VOTING_PERIOD);
+    client.approve_upgrade(&proposal_id, &approver);
+
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time + EXECUTION_WINDOW);
+    client.execute_upgrade(&proposal_id, &executor);
+
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Executed);
+}
+
+/// Ledger entries approving the last of `count` proposals touches, entries it writes, and their size
+fn approval_footprint(count: u32) -> (usize, usize, usize) {
+    use soroban_sdk::xdr::{Limits, WriteXdr};
+
+    let env = Env::default();
+    let (client, admin, approver, _executor) = setup_governance(&env);
+
+    let mut proposal_id = 0;
+    for _ in 0..count {
+        proposal_id = client.propose_upgrade(
+            &admin,
+            &symbol_short!("v2hash"),
+            &symbol_short!("Upgrade"),
+        );
+    }
+
+    // Start from an empty footprint so it only records what the approval touches
+    env.host()
+        .with_mut_storage(|storage| {
+            storage.footprint = Default::default();
+            Ok(())
+        })
+        .unwrap();
+    let before = env.to_snapshot().ledger.ledger_entries;
+
+    client.approve_upgrade(&proposal_id, &approver);
+
+    let touched = env.host().with_mut_storage(|storage| Ok(storage.footprint.0.len())).unwrap();
+    let after = env.to_snapshot().ledger.ledger_entries;
+    let written = after.iter().filter(|entry| !before.contains(entry));
+    let bytes = written
+        .clone()
+        .map(|(_, (entry, _))| entry.to_xdr(Limits::none()).unwrap().len())
+        .sum();
+    (touched, written.count(), bytes)
+}
+
+#[test]
+fn test_approval_cost_flat_as_proposals_grow() {
+    let (touched, written, bytes) = approval_footprint(2);
+    assert!(written > 0 && written <= touched);
+
+    // Per-proposal keys: approving #50 reads and writes as many entries, and
+    // as many bytes, as approving #2
+    assert_eq!(approval_footprint(50), (touched, written, bytes));
+}
+
+#[test]
+fn test_migrate_storage_converts_legacy_proposals() {
+    let env = Env::default();
+    let (client, admin, approver, _executor) = setup_governance(&env);
+
+    // Baseline layout: proposals with a bare code hash in one map, approvals in another
+    let legacy = |id: u64, hash: Symbol, status: ProposalStatus| LegacyUpgradeProposal {
+        id,
+        proposer: admin.clone(),
+        new_contract_hash: hash,
+        target_contract: client.address.clone(),
+        description: symbol_short!("Upgrade"),
+        approval_threshold: 1,
+        approvers: soroban_sdk::vec![&env, approver.clone()],
+        approvals_count: if status == ProposalStatus::Pending { 0 } else { 1 },
+        status,
+        created_at: 500,
+        execution_time: 4100,
+        executed: false,
+    };
+    let mut proposals = soroban_sdk::Map::new(&env);
+    proposals.set(1u64, legacy(1, symbol_short!("v2hash"), ProposalStatus::Approved));
+    proposals.set(2, legacy(2, symbol_short!("v3hash"), ProposalStatus::Pending));
+    let mut approvals = soroban_sdk::Map::new(&env);
+    approvals.set((1u64, approver.clone()), true);
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(&symbol_short!("props"), &proposals);
+        env.storage().persistent().set(&symbol_short!("apprv"), &approvals);
+        env.storage().persistent().set(&symbol_short!("prop_cnt"), &2u64);
+    });
+
+    assert_eq!(client.migrate_governance(&admin), 2);
+
+    let prop = client.get_upgrade_proposal(&1);
+    assert_eq!(prop.actions, soroban_sdk::vec![&env, ProposalAction::Upgrade(symbol_short!("v2hash"))]);
+    assert_eq!(prop.status, ProposalStatus::Approved);
+    assert_eq!((prop.created_at, prop.execution_time), (500, 4100));
+    assert_eq!(prop.voting_deadline, 500 + VOTING_PERIOD);
+    assert_eq!(
+        client.get_council_votes(&1),
+        soroban_sdk::vec![&env, ApproverVote { approver: approver.clone(), vote: CouncilVote::Approve }]
+    );
+    assert_eq!(client.get_upgrade_proposal(&2).status, ProposalStatus::Pending);
+
+    // The legacy maps are gone and new proposals carry on from the old counter
+    env.as_contract(&client.address, || assert!(!env.storage().persistent().has(&symbol_short!("props"))));
+    assert_eq!(client.migrate_governance(&admin), 0);
+    assert_eq!(client.propose_upgrade(&admin, &symbol_short!("v4hash"), &symbol_short!("Upgrade")), 3);
+}
+
+#[test]
+fn test_list_proposals_pages_and_filters() {
+    let env = Env::default();
+    let (client, admin, approver, _executor) = setup_governance(&env);
+
+    for _ in 0..5 {
+        client.propose_upgrade(
+            &admin,
+            &symbol_short!("v2hash"),
+            &symbol_short!("Upgrade"),
+        );
+    }
+    client.approve_upgrade(&2, &approver);
+    client.approve_upgrade(&4, &approver);
+
+    let page = client.list_proposals(&None, &0, &3);
+    assert_eq!(page.proposals.len(), 3);
+    assert_eq!(page.next_cursor, Some(3));
+
+    let page = client.list_proposals(&None, &3, &3);
+    assert_eq!(page.proposals.len(), 2);
+    assert_eq!(page.next_cursor, None);
+
+    let approved = client.list_proposals(&Some(ProposalStatus::Approved), &0, &10);
+    assert_eq!(approved.proposals.len(), 2);
+    assert_eq!(approved.proposals.get(0).unwrap().id, 2);
+    assert_eq!(approved.proposals.get(1).unwrap().id, 4);
+}
+
+#[test]
+fn test_council_changes_only_through_proposal() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    let council = Council {
+        members: soroban_sdk::vec![&env, member1.clone(), member2.clone()],
+        threshold: 2,
+        veto_threshold: 1,
+    };
+
+    let proposal_id = client.propose_action(
+        &admin,
+        &ProposalAction::SetCouncil(council.clone()),
+        &symbol_short!("council"),
+    );
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.approvers, soroban_sdk::vec![&env, approver.clone()]);
+    assert_eq!(prop.approval_threshold, 1);
+
+    client.approve_upgrade(&proposal_id, &approver);
+    pass_timelock(&env, &client, proposal_id);
+    client.execute_upgrade(&proposal_id, &executor);
+    assert_eq!(client.get_council(), council);
+
+    // The outgoing member loses the approver role; new proposals use the new council
+    assert!(!client.has_role(&approver, &GovernanceRole::Approver));
+    let next_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let next = client.get_upgrade_proposal(&next_id);
+    assert_eq!(next.approvers, council.members);
+    assert_eq!(next.approval_threshold, 2);
+    assert_eq!(client.try_approve_upgrade(&next_id, &approver), Err(Ok(GovernanceError::Unauthorized)));
+}
+
+fn setup_council(
+    env: &Env,
+) -> (UpgradeableTradingContractClient<'_>, Address, [Address; 3], Address) {
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let contract_id = env.register_contract(None, UpgradeableTradingContract);
+    let client = UpgradeableTradingContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let members = [Address::generate(env), Address::generate(env), Address::generate(env)];
+    let executor = Address::generate(env);
+
+    // 3 members: approval and veto thresholds both default to 2
+    let approvers = soroban_sdk::vec![env, members[0].clone(), members[1].clone(), members[2].clone()];
+    client.init(&admin, &approvers, &executor);
+
+    (client, admin, members, executor)
+}
+
+#[test]
+fn test_rejection_needs_veto_threshold() {
+    let env = Env::default();
+    let (client, admin, members, _executor) = setup_council(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+
+    client.reject_upgrade(&proposal_id, &members[0]);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Pending);
+
+    // A member who rejected cannot also approve
+    assert_eq!(client.try_approve_upgrade(&proposal_id, &members[0]), Err(Ok(GovernanceError::DuplicateApproval)));
+
+    client.reject_upgrade(&proposal_id, &members[1]);
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.status, ProposalStatus::Rejected);
+    assert_eq!(prop.rejections_count, 2);
+
+    let votes = client.get_council_votes(&proposal_id);
+    assert_eq!(votes.len(), 2);
+    assert_eq!(votes.get(0).unwrap().vote, CouncilVote::Reject);
+}
+
+#[test]
+fn test_revoked_approval_drops_proposal_out_of_approved() {
+    let env = Env::default();
+    let (client, admin, members, executor) = setup_council(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    client.approve_upgrade(&proposal_id, &members[0]);
+    client.approve_upgrade(&proposal_id, &members[1]);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Approved);
+
+    // Still inside the timelock: withdrawing falls back below 2 of 3
+    client.revoke_approval(&proposal_id, &members[1]);
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.status, ProposalStatus::Pending);
+    assert_eq!(prop.approvals_count, 1);
+    assert_eq!(prop.execution_time, 0); // The timelock is cleared with the approval
+    assert_eq!(client.try_revoke_approval(&proposal_id, &members[1]), Err(Ok(GovernanceError::ApprovalNotFound)));
+
+    env.ledger().with_mut(|li| li.timestamp += DEFAULT_UPGRADE_TIMELOCK);
+    assert_eq!(client.try_execute_upgrade(&proposal_id, &executor), Err(Ok(GovernanceError::ProposalNotApproved)));
+
+    // Once approved and past the timelock, approvals are locked in
+    client.approve_upgrade(&proposal_id, &members[2]);
+    pass_timelock(&env, &client, proposal_id);
+    assert_eq!(client.try_revoke_approval(&proposal_id, &members[2]), Err(Ok(GovernanceError::InvalidProposal)));
+    client.execute_upgrade(&proposal_id, &executor);
+}
+
+#[test]
+fn test_emergency_pause_needs_unanimous_council_and_no_timelock() {
+    let env = Env::default();
+    let (client, admin, members, executor) = setup_council(&env);
+
+    // Only whitelisted emergency actions are accepted
+    assert_eq!(
+        client.try_propose_emergency(&admin, &ProposalAction::SetParam(symbol_short!("fee"), 5), &symbol_short!("fee")),
+        Err(Ok(GovernanceError::InvalidAction))
+    );
+
+    // Governance pauses are bounded like direct ones, whichever way they're proposed
+    for duration in [0, MAX_PAUSE_DURATION + 1] {
+        let action = ProposalAction::Pause(PauseScope::Global, duration);
+        assert_eq!(
+            client.try_propose_emergency(&admin, &action, &symbol_short!("exploit")),
+            Err(Ok(GovernanceError::InvalidAction))
+        );
+        assert_eq!(
+            client.try_propose_action(&admin, &action, &symbol_short!("exploit")),
+            Err(Ok(GovernanceError::InvalidAction))
+        );
+    }
+
+    let proposal_id = client.propose_emergency(
+        &admin,
+        &ProposalAction::Pause(PauseScope::Global, 3600),
+        &symbol_short!("exploit"),
+    );
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert!(prop.emergency);
+    assert_eq!(prop.approval_threshold, 3);
+    assert_eq!(prop.timelock, 0);
+
+    // The regular 2-of-3 majority is not enough
+    client.approve_upgrade(&proposal_id, &members[0]);
+    client.approve_upgrade(&proposal_id, &members[1]);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).status, ProposalStatus::Pending);
+
+    client.approve_upgrade(&proposal_id, &members[2]);
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).execution_time, env.ledger().timestamp());
+    client.execute_upgrade(&proposal_id, &executor);
+    assert!(client.get_pause(&PauseScope::Global).is_some());
+}
+
+#[test]
+fn test_active_pause_only_extends_through_governance() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let guardian = Address::generate(&env);
+    let scope = PauseScope::Function(symbol_short!("trade"));
+
+    let id = client.grant_role(&admin, &guardian, &GovernanceRole::Guardian);
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    // A guardian can't keep a pause alive by pausing again
+    client.pause(&guardian, &scope, &3_600);
+    let expires_at = client.get_pause(&scope).unwrap().expires_at;
+    assert_eq!(client.try_pause(&guardian, &scope, &3_600), Err(Ok(TradeError::AlreadyPaused)));
+    assert_eq!(client.try_pause(&admin, &scope, &3_600), Err(Ok(TradeError::AlreadyPaused)));
+
+    // Extensions are bounded proposals; as emergencies they skip the timelock
+    assert_eq!(
+        client.try_propose_emergency(&admin, &ProposalAction::ExtendPause(scope.clone(), MAX_PAUSE_DURATION + 1), &symbol_short!("ext")),
+        Err(Ok(GovernanceError::InvalidAction))
+    );
+    let id = client.propose_emergency(&admin, &ProposalAction::ExtendPause(scope.clone(), 86_400), &symbol_short!("ext"));
+    client.approve_upgrade(&id, &approver);
+    client.execute_upgrade(&id, &executor);
+    assert_eq!(client.get_pause(&scope).unwrap().expires_at, expires_at + 86_400);
+
+    // Once the pause has lapsed there is nothing to extend
+    let expires_at = expires_at + 86_400;
+    env.ledger().with_mut(|li| li.timestamp = expires_at);
+    let id = client.propose_emergency(&admin, &ProposalAction::ExtendPause(scope.clone(), 86_400), &symbol_short!("ext"));
+    client.approve_upgrade(&id, &approver);
+    assert_eq!(client.try_execute_upgrade(&id, &executor), Err(Ok(GovernanceError::ActionFailed)));
+    let event: ActionFailedEvent = env.events().all().last().unwrap().2.into_val(&env);
+    assert_eq!(event.code, GovernanceError::NotPaused as u32);
+    assert!(client.get_pause(&scope).is_none());
+
+    // Nor can the guardian pause again straight away, though governance still can
+    assert_eq!(client.try_pause(&guardian, &scope, &3_600), Err(Ok(TradeError::PauseCooldown)));
+    env.ledger().with_mut(|li| li.timestamp = expires_at + PAUSE_COOLDOWN - 1);
+    assert_eq!(client.try_pause(&guardian, &scope, &3_600), Err(Ok(TradeError::PauseCooldown)));
+    let id = client.propose_emergency(&admin, &ProposalAction::Pause(scope.clone(), 60), &symbol_short!("pause"));
+    client.approve_upgrade(&id, &approver);
+    client.execute_upgrade(&id, &executor);
+    assert!(client.get_pause(&scope).is_some());
+
+    // The cooldown runs from whichever pause lapsed last
+    let expires_at = client.get_pause(&scope).unwrap().expires_at;
+    env.ledger().with_mut(|li| li.timestamp = expires_at + PAUSE_COOLDOWN);
+    client.pause(&guardian, &scope, &3_600);
+
+    // An extension that would run past the end of time fails instead of wrapping
+    env.as_contract(&client.address, || {
+        let mut state = PauseManager::get(&env, &scope).unwrap();
+        state.expires_at = u64::MAX - 1;
+        PauseManager::set(&env, &scope, &state);
+    });
+    let id = client.propose_emergency(&admin, &ProposalAction::ExtendPause(scope.clone(), 86_400), &symbol_short!("ext"));
+    client.approve_upgrade(&id, &approver);
+    assert_eq!(client.try_execute_upgrade(&id, &executor), Err(Ok(GovernanceError::ActionFailed)));
+    assert_eq!(client.get_pause(&scope).unwrap().expires_at, u64::MAX - 1);
+}
+
+#[test]
+fn test_multi_action_proposal_is_atomic() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let guardian = Address::generate(&env);
+    let compliance = Address::generate(&env);
+
+    // Second action fails (zero-amount transfer), so the grant must not stick
+    let failing = client.propose_actions(
+        &admin,
+        &soroban_sdk::vec![
+            &env,
+            ProposalAction::GrantRole(guardian.clone(), GovernanceRole::Guardian),
+            ProposalAction::TransferTreasury(admin.clone(), admin.clone(), 0),
+        ],
+        &symbol_short!("bad"),
+    );
+    client.approve_upgrade(&failing, &approver);
+    pass_timelock(&env, &client, failing);
+    assert_eq!(client.try_execute_upgrade(&failing, &executor), Err(Ok(GovernanceError::ActionFailed)));
+    assert!(!client.has_role(&guardian, &GovernanceRole::Guardian));
+
+    // The failed run still reports which action failed and with what code
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("act_fail"),).into_val(&env));
+    let event: ActionFailedEvent = data.into_val(&env);
+    assert_eq!((event.proposal_id, event.index), (failing, 1));
+    assert_eq!(event.code, GovernanceError::InvalidAction as u32);
+    assert_eq!(client.get_upgrade_proposal(&failing).status, ProposalStatus::Approved);
+
+    let proposal_id = client.propose_actions(
+        &admin,
+        &soroban_sdk::vec![
+            &env,
+            ProposalAction::GrantRole(guardian.clone(), GovernanceRole::Guardian),
+            ProposalAction::GrantRole(compliance.clone(), GovernanceRole::Compliance),
+        ],
+        &symbol_short!("roles"),
+    );
+    client.approve_upgrade(&proposal_id, &approver);
+    pass_timelock(&env, &client, proposal_id);
+    client.execute_upgrade(&proposal_id, &executor);
+
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.status, ProposalStatus::Executed);
+    assert_eq!(prop.outcomes, soroban_sdk::vec![&env, ActionOutcome::Succeeded, ActionOutcome::Succeeded]);
+    assert!(client.has_role(&guardian, &GovernanceRole::Guardian));
+    assert!(client.has_role(&compliance, &GovernanceRole::Compliance));
+}
+
+#[test]
+fn test_role_changes_go_through_timelocked_proposals() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let new_executor = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    // Only the role's admin role may propose, and only held roles can be revoked
+    assert_eq!(
+        client.try_grant_role(&approver, &new_executor, &GovernanceRole::Executor),
+        Err(Ok(GovernanceError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_revoke_role(&admin, &new_executor, &GovernanceRole::Executor),
+        Err(Ok(GovernanceError::RoleNotHeld))
+    );
+    assert_eq!(
+        client.try_revoke_role(&admin, &admin, &GovernanceRole::Admin),
+        Err(Ok(GovernanceError::LastAdmin))
+    );
+
+    // Replace the executor: nothing changes until the roles timelock has passed
+    let grant = client.grant_role(&admin, &new_executor, &GovernanceRole::Executor);
+    let revoke = client.revoke_role(&admin, &executor, &GovernanceRole::Executor);
+    for id in [grant, revoke] {
+        client.approve_upgrade(&id, &approver);
+        assert_eq!(client.get_upgrade_proposal(&id).execution_time, env.ledger().timestamp() + DEFAULT_ROLES_TIMELOCK);
+    }
+    assert_eq!(client.try_execute_upgrade(&grant, &executor), Err(Ok(GovernanceError::TimelockNotExpired)));
+    assert!(!client.has_role(&new_executor, &GovernanceRole::Executor));
+
+    pass_timelock(&env, &client, revoke);
+    client.execute_upgrade(&grant, &executor);
+    client.execute_upgrade(&revoke, &new_executor);
+    assert_eq!(client.get_role_members(&GovernanceRole::Executor), soroban_sdk::vec![&env, new_executor.clone()]);
+    assert!(!client.has_role(&executor, &GovernanceRole::Executor));
+
+    // The old executor can no longer execute
+    let id = client.grant_role(&admin, &new_admin, &GovernanceRole::Admin);
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    assert_eq!(client.try_execute_upgrade(&id, &executor), Err(Ok(GovernanceError::Unauthorized)));
+    client.execute_upgrade(&id, &new_executor);
+
+    // With a second admin, the first can be removed, but not both
+    let first = client.revoke_role(&new_admin, &admin, &GovernanceRole::Admin);
+    let second = client.revoke_role(&new_admin, &new_admin, &GovernanceRole::Admin);
+    for id in [first, second] {
+        client.approve_upgrade(&id, &approver);
+    }
+    pass_timelock(&env, &client, second);
+    client.execute_upgrade(&first, &new_executor);
+    assert_eq!(client.try_execute_upgrade(&second, &new_executor), Err(Ok(GovernanceError::ActionFailed)));
+    assert_eq!(client.get_role_members(&GovernanceRole::Admin), soroban_sdk::vec![&env, new_admin]);
+}
+
+#[test]
+fn test_treasury_call_and_pause_actions_execute() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let token_id = env.register_stellar_asset_contract(Address::generate(&env));
+    let token_client = token::Client::new(&env, &token_id);
+    token::StellarAssetClient::new(&env, &token_id).mint(&client.address, &500);
+    let recipient = Address::generate(&env);
+
+    let transfer_call = |amount: i128| {
+        let args = soroban_sdk::vec![
+            &env,
+            client.address.into_val(&env),
+            recipient.into_val(&env),
+            amount.into_val(&env),
+        ];
+        ProposalAction::Call(token_id.clone(), Symbol::new(&env, "transfer"), args)
+    };
+    let run = |actions: Vec<ProposalAction>| {
+        let id = client.propose_actions(&admin, &actions, &symbol_short!("ops"));
+        client.approve_upgrade(&id, &approver);
+        pass_timelock(&env, &client, id);
+        (id, client.try_execute_upgrade(&id, &executor))
+    };
+
+    let (id, result) = run(soroban_sdk::vec![
+        &env,
+        ProposalAction::TransferTreasury(token_id.clone(), recipient.clone(), 200),
+        transfer_call(50),
+        ProposalAction::Pause(PauseScope::Global, 86_400),
+    ]);
+    assert!(result.is_ok());
+    assert_eq!(
+        client.get_upgrade_proposal(&id).outcomes,
+        soroban_sdk::vec![&env, ActionOutcome::Succeeded, ActionOutcome::Succeeded, ActionOutcome::Succeeded]
+    );
+    assert_eq!(token_client.balance(&recipient), 250);
+    assert_eq!(token_client.balance(&client.address), 250);
+    assert!(client.get_pause(&PauseScope::Global).is_some());
+
+    // A transfer the treasury can't cover fails with its own code and moves nothing
+    let (id, result) = run(soroban_sdk::vec![
+        &env,
+        ProposalAction::Unpause(PauseScope::Global),
+        ProposalAction::TransferTreasury(token_id.clone(), recipient.clone(), 1_000),
+    ]);
+    assert_eq!(result, Err(Ok(GovernanceError::ActionFailed)));
+    let event: ActionFailedEvent = env.events().all().last().unwrap().2.into_val(&env);
+    assert_eq!((event.index, event.code), (1, GovernanceError::TransferFailed as u32));
+    assert_eq!(client.get_upgrade_proposal(&id).status, ProposalStatus::Approved);
+    assert!(client.get_pause(&PauseScope::Global).is_some());
+
+    // So does a call the target refuses
+    let (_, result) = run(soroban_sdk::vec![&env, transfer_call(1_000)]);
+    assert_eq!(result, Err(Ok(GovernanceError::ActionFailed)));
+    assert_eq!(token_client.balance(&recipient), 250);
+
+    let (_, result) = run(soroban_sdk::vec![&env, ProposalAction::Unpause(PauseScope::Global)]);
+    assert!(result.is_ok());
+    assert!(client.get_pause(&PauseScope::Global).is_none());
+}
+
+#[test]
+fn test_roles_are_explicit_and_combine_per_address() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let stranger = Address::generate(&env);
+    let officer = Address::generate(&env);
+
+    // An address with no roles gets a typed error, not an implicit Executor role
+    let id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    assert_eq!(client.try_execute_upgrade(&id, &stranger), Err(Ok(GovernanceError::Unauthorized)));
+    assert_eq!(
+        client.try_propose_upgrade(&stranger, &symbol_short!("v3hash"), &symbol_short!("Upgrade")),
+        Err(Ok(GovernanceError::Unauthorized))
+    );
+
+    // One address can hold several roles at once
+    let id = client.propose_actions(
+        &admin,
+        &soroban_sdk::vec![
+            &env,
+            ProposalAction::GrantRole(admin.clone(), GovernanceRole::Executor),
+            ProposalAction::SetRoleAdmin(GovernanceRole::Compliance, GovernanceRole::Executor),
+        ],
+        &symbol_short!("roles"),
+    );
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    assert!(client.has_role(&admin, &GovernanceRole::Admin));
+    assert!(client.has_role(&admin, &GovernanceRole::Executor));
+    assert_eq!(
+        client.get_role_members(&GovernanceRole::Executor),
+        soroban_sdk::vec![&env, executor.clone(), admin.clone()]
+    );
+
+    // Compliance changes are now proposed by Executors, not Admins
+    assert_eq!(client.get_role_admin(&GovernanceRole::Compliance), GovernanceRole::Executor);
+    assert_eq!(client.get_role_admin(&GovernanceRole::Guardian), GovernanceRole::Admin);
+    assert_eq!(
+        client.try_grant_role(&approver, &officer, &GovernanceRole::Compliance),
+        Err(Ok(GovernanceError::Unauthorized))
+    );
+    let id = client.grant_role(&executor, &officer, &GovernanceRole::Compliance);
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &admin);
+    assert!(client.has_role(&officer, &GovernanceRole::Compliance));
+    assert!(!client.has_role(&officer, &GovernanceRole::Executor));
+}
+
+#[test]
+fn test_signature_batch_rejected_as_a_group() {
+    let env = Env::default();
+    let (client, admin, members, _executor) = setup_council(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    client.set_signing_key(&members[0], &BytesN::from_array(&env, &[1; 32]));
+
+    let signature = BytesN::from_array(&env, &[0; 64]);
+    let entry = |approver: &Address| ApprovalSignature {
+        approver: approver.clone(),
+        signature: signature.clone(),
+    };
+
+    // Same signer twice
+    let duplicate = soroban_sdk::vec![&env, entry(&members[0]), entry(&members[0])];
+    assert_eq!(client.try_approve_with_signatures(&proposal_id, &duplicate), Err(Ok(GovernanceError::DuplicateApproval)));
+
+    // Second signer never registered a key
+    let unregistered = soroban_sdk::vec![&env, entry(&members[0]), entry(&members[1])];
+    assert_eq!(client.try_approve_with_signatures(&proposal_id, &unregistered), Err(Ok(GovernanceError::SigningKeyNotSet)));
+
+    // Nothing from either batch was recorded
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).approvals_count, 0);
+    assert_eq!(client.get_council_votes(&proposal_id).len(), 0);
+}
+
+/// Council of three with ed25519 keys registered for the first two members
+fn setup_signing_council(
+    env: &Env,
+) -> (UpgradeableTradingContractClient<'_>, Address, [Address; 3], [ed25519_dalek::SigningKey; 2]) {
+    let (client, admin, members, _executor) = setup_council(env);
+    let keys = [
+        ed25519_dalek::SigningKey::from_bytes(&[1; 32]),
+        ed25519_dalek::SigningKey::from_bytes(&[2; 32]),
+    ];
+    for (member, key) in members.iter().zip(keys.iter()) {
+        client.set_signing_key(member, &BytesN::from_array(env, &key.verifying_key().to_bytes()));
+    }
+    (client, admin, members, keys)
+}
+
+/// Approval by `approver`, signed with `key` over the proposal's digest
+fn sign_approval(
+    env: &Env,
+    client: &UpgradeableTradingContractClient,
+    proposal_id: u64,
+    approver: &Address,
+    key: &ed25519_dalek::SigningKey,
+) -> ApprovalSignature {
+    use ed25519_dalek::Signer;
+
+    let digest = client.get_approval_digest(&proposal_id).to_array();
+    ApprovalSignature {
+        approver: approver.clone(),
+        signature: BytesN::from_array(env, &key.sign(&digest).to_bytes()),
+    }
+}
+
+#[test]
+fn test_signed_approvals_verify_against_registered_keys() {
+    let env = Env::default();
+    let (client, admin, members, keys) = setup_signing_council(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let batch = soroban_sdk::vec![
+        &env,
+        sign_approval(&env, &client, proposal_id, &members[0], &keys[0]),
+        sign_approval(&env, &client, proposal_id, &members[1], &keys[1]),
+    ];
+    assert_eq!(client.approve_with_signatures(&proposal_id, &batch), 2);
+
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.approvals_count, 2);
+    assert_eq!(prop.status, ProposalStatus::Approved);
+    assert_eq!(client.get_council_votes(&proposal_id).len(), 2);
+}
+
+/// Relay a batch whose second signature has one bit flipped.
+///
+/// A failed `ed25519_verify` traps, and a trap inside a native test contract
+/// aborts the process on current toolchains, so
+/// `test_tampered_signature_fails_the_whole_batch` runs this in a child process.
+#[test]
+fn tampered_signature_batch() {
+    if std::env::var("TAMPERED_BATCH").is_err() {
+        return;
+    }
+    let env = Env::default();
+    let (client, admin, members, keys) = setup_signing_council(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let mut tampered = sign_approval(&env, &client, proposal_id, &members[1], &keys[1]);
+    let mut bytes = tampered.signature.to_array();
+    bytes[0] ^= 1;
+    tampered.signature = BytesN::from_array(&env, &bytes);
+
+    let valid = sign_approval(&env, &client, proposal_id, &members[0], &keys[0]);
+    let batch = soroban_sdk::vec![&env, valid, tampered];
+    std::eprintln!("relaying tampered batch");
+    assert!(client.try_approve_with_signatures(&proposal_id, &batch).is_err());
+
+    // The valid signature ahead of the tampered one wasn't recorded either
+    assert_eq!(client.get_upgrade_proposal(&proposal_id).approvals_count, 0);
+    assert_eq!(client.get_council_votes(&proposal_id).len(), 0);
+}
+
+#[test]
+fn test_tampered_signature_fails_the_whole_batch() {
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "test::tampered_signature_batch", "--nocapture"])
+        .env("TAMPERED_BATCH", "1")
+        .output()
+        .unwrap();
+
+    // Either the trap aborted the relay call, or it unwound and the checks after it passed
+    let stderr = std::string::String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("relaying tampered batch"), "{}", stderr);
+    assert!(output.status.success() || stderr.contains("non-unwinding panic"), "{}", stderr);
+}
+
+#[test]
+fn test_guardian_veto_during_timelock_is_rate_limited() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let guardian = Address::generate(&env);
+
+    let grant_id = client.grant_role(&admin, &guardian, &GovernanceRole::Guardian);
+    client.approve_upgrade(&grant_id, &approver);
+    pass_timelock(&env, &client, grant_id);
+    client.execute_upgrade(&grant_id, &executor);
+
+    // Pending proposals can't be vetoed, only approved ones
+    let first = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    assert_eq!(client.try_veto_upgrade(&first, &guardian), Err(Ok(GovernanceError::InvalidProposal)));
+    client.approve_upgrade(&first, &approver);
+    assert_eq!(client.try_veto_upgrade(&first, &admin), Err(Ok(GovernanceError::Unauthorized)));
+
+    client.veto_upgrade(&first, &guardian);
+    let prop = client.get_upgrade_proposal(&first);
+    assert_eq!(prop.status, ProposalStatus::Vetoed);
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time);
+    assert_eq!(client.try_execute_upgrade(&first, &executor), Err(Ok(GovernanceError::ProposalNotApproved)));
+
+    // A second veto inside the cooldown is refused
+    let second = client.propose_upgrade(&admin, &symbol_short!("v3hash"), &symbol_short!("Upgrade"));
+    client.approve_upgrade(&second, &approver);
+    assert_eq!(client.try_veto_upgrade(&second, &guardian), Err(Ok(GovernanceError::VetoRateLimited)));
+
+    // Rotating the guardian out can never be vetoed, cooldown or not
+    let rotate = client.revoke_role(&admin, &guardian, &GovernanceRole::Guardian);
+    client.approve_upgrade(&rotate, &approver);
+    assert_eq!(client.try_veto_upgrade(&rotate, &guardian), Err(Ok(GovernanceError::InvalidProposal)));
+}
+
+#[test]
+fn test_late_approval_still_gets_full_veto_window() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let guardian = Address::generate(&env);
+
+    let grant_id = client.grant_role(&admin, &guardian, &GovernanceRole::Guardian);
+    client.approve_upgrade(&grant_id, &approver);
+    pass_timelock(&env, &client, grant_id);
+    client.execute_upgrade(&grant_id, &executor);
+
+    // Approve near the end of the vote, long after a creation-anchored timelock would have run out
+    let id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+    let created_at = client.get_upgrade_proposal(&id).created_at;
+    env.ledger().with_mut(|li| li.timestamp = created_at + VOTING_PERIOD - 1);
+    client.approve_upgrade(&id, &approver);
+
+    let approved_at = env.ledger().timestamp();
+    assert_eq!(client.get_upgrade_proposal(&id).execution_time, approved_at + DEFAULT_UPGRADE_TIMELOCK);
+    assert_eq!(client.try_execute_upgrade(&id, &executor), Err(Ok(GovernanceError::TimelockNotExpired)));
+
+    // The guardian can still act until the last second of the delay
+    env.ledger().with_mut(|li| li.timestamp = approved_at + DEFAULT_UPGRADE_TIMELOCK - 1);
+    client.veto_upgrade(&id, &guardian);
+    assert_eq!(client.get_upgrade_proposal(&id).status, ProposalStatus::Vetoed);
+}
+
+#[test]
+fn test_mixed_batch_with_guardian_rotation_can_be_vetoed() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let guardian = Address::generate(&env);
+
+    let grant_id = client.grant_role(&admin, &guardian, &GovernanceRole::Guardian);
+    client.approve_upgrade(&grant_id, &approver);
+    pass_timelock(&env, &client, grant_id);
+    client.execute_upgrade(&grant_id, &executor);
+
+    // Riding along with a rotation doesn't shield an upgrade from the guardian
+    let mixed = client.propose_actions(
+        &admin,
+        &soroban_sdk::vec![
+            &env,
+            ProposalAction::GrantRole(Address::generate(&env), GovernanceRole::Guardian),
+            ProposalAction::Upgrade(symbol_short!("v2hash")),
+        ],
+        &symbol_short!("mixed"),
+    );
+    client.approve_upgrade(&mixed, &approver);
+    client.veto_upgrade(&mixed, &guardian);
+    assert_eq!(client.get_upgrade_proposal(&mixed).status, ProposalStatus::Vetoed);
+}
+
+#[test]
+fn test_min_timelock_floors_every_non_emergency_proposal() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    assert_eq!(client.get_param(&MIN_TIMELOCK_PARAM), Some(DEFAULT_MIN_TIMELOCK as i128));
+
+    // The floor can't be governed below its default, and class timelocks can't exceed the cap
+    assert_eq!(
+        client.try_propose_action(&admin, &ProposalAction::SetParam(MIN_TIMELOCK_PARAM, 0), &symbol_short!("tl")),
+        Err(Ok(GovernanceError::ParamOutOfBounds))
+    );
+    assert_eq!(
+        client.try_propose_action(
+            &admin,
+            &ProposalAction::SetParam(TIMELOCK_OPS_PARAM, MAX_TIMELOCK as i128 + 1),
+            &symbol_short!("tl"),
+        ),
+        Err(Ok(GovernanceError::ParamOutOfBounds))
+    );
+
+    // Zero out the ops timelock and raise the floor to 2 days
+    let batch = client.propose_actions(
+        &admin,
+        &soroban_sdk::vec![
+            &env,
+            ProposalAction::SetParam(TIMELOCK_OPS_PARAM, 0),
+            ProposalAction::SetParam(MIN_TIMELOCK_PARAM, 172_800),
+        ],
+        &symbol_short!("tl"),
+    );
+    let prop = client.get_upgrade_proposal(&batch);
+    assert_eq!(prop.timelock, DEFAULT_CONFIG_TIMELOCK);
+    client.approve_upgrade(&batch, &approver);
+    pass_timelock(&env, &client, batch);
+    client.execute_upgrade(&batch, &executor);
+
+    // Timelock parameter changes wait out their own delay
+    assert_eq!(client.get_param(&TIMELOCK_OPS_PARAM), Some(DEFAULT_OPS_TIMELOCK as i128));
+    env.ledger().with_mut(|li| li.timestamp += TIMELOCK_CHANGE_DELAY);
+    assert_eq!(client.get_param(&TIMELOCK_OPS_PARAM), Some(0));
+
+    // An ops action now waits out the floor, not its zero class timelock
+    let pause = client.propose_action(
+        &admin,
+        &ProposalAction::Pause(PauseScope::Global, 3_600),
+        &symbol_short!("pause"),
+    );
+    let prop = client.get_upgrade_proposal(&pause);
+    assert_eq!(prop.timelock, 172_800);
+
+    // Emergency proposals stay exempt
+    let emergency = client.propose_emergency(
+        &admin,
+        &ProposalAction::Pause(PauseScope::Global, 3_600),
+        &symbol_short!("pause"),
+    );
+    let prop = client.get_upgrade_proposal(&emergency);
+    assert_eq!(prop.timelock, 0);
+}
+
+#[test]
+fn test_param_changes_are_bounded_and_delayed() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let key = symbol_short!("max_lev");
+
+    // Unknown keys can't be set
+    assert_eq!(
+        client.try_propose_action(&admin, &ProposalAction::SetParam(key.clone(), 5), &symbol_short!("lev")),
+        Err(Ok(GovernanceError::UnknownParam))
+    );
+
+    let spec = ParamSpec { min: 1, max: 10, change_delay: 3600 };
+    let define = client.propose_action(&admin, &ProposalAction::DefineParam(key.clone(), spec, 2), &symbol_short!("lev"));
+    client.approve_upgrade(&define, &approver);
+    pass_timelock(&env, &client, define);
+    client.execute_upgrade(&define, &executor);
+    assert_eq!(client.get_param(&key), Some(2));
+
+    // Out-of-bounds values are refused at proposal time
+    assert_eq!(
+        client.try_propose_action(&admin, &ProposalAction::SetParam(key.clone(), 11), &symbol_short!("lev")),
+        Err(Ok(GovernanceError::ParamOutOfBounds))
+    );
+
+    let change = client.propose_action(&admin, &ProposalAction::SetParam(key.clone(), 5), &symbol_short!("lev"));
+    client.approve_upgrade(&change, &approver);
+    pass_timelock(&env, &client, change);
+    client.execute_upgrade(&change, &executor);
+
+    // The old value holds until the change delay passes
+    assert_eq!(client.get_param(&key), Some(2));
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(client.get_param(&key), Some(5));
+
+    let history = client.get_param_history(&key);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().old_value, 2);
+    assert_eq!(history.get(0).unwrap().new_value, 5);
+}
+
+#[test]
+fn test_rollback_restores_previous_hash_unless_schema_moved() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+
+    // Nothing to roll back to yet
+    assert_eq!(client.try_propose_rollback(&admin, &symbol_short!("undo")), Err(Ok(GovernanceError::NoPreviousVersion)));
+
+    for hash in [symbol_short!("v2hash"), symbol_short!("v3hash")] {
+        let id = client.propose_upgrade(&admin, &hash, &symbol_short!("Upgrade"));
+        client.approve_upgrade(&id, &approver);
+        pass_timelock(&env, &client, id);
+        client.execute_upgrade(&id, &executor);
+    }
+    let history = client.get_upgrade_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(1).unwrap().previous_hash, PreviousHash::Hash(symbol_short!("v2hash")));
+
+    // v3 migrated storage forward and has no down-migration
+    env.as_contract(&client.address, || UpgradeManager::set_schema_version(&env, 2));
+    assert_eq!(client.try_propose_rollback(&admin, &symbol_short!("undo")), Err(Ok(GovernanceError::SchemaAhead)));
+
+    // Once a down-migration restores the schema, rollback goes through the short timelock
+    env.as_contract(&client.address, || UpgradeManager::set_schema_version(&env, 1));
+    let rollback = client.propose_rollback(&admin, &symbol_short!("undo"));
+    assert_eq!(client.get_upgrade_proposal(&rollback).timelock, shared::upgrade::ROLLBACK_TIMELOCK);
+
+    client.approve_upgrade(&rollback, &approver);
+    let prop = client.get_upgrade_proposal(&rollback);
+    assert_eq!(prop.execution_time, env.ledger().timestamp() + shared::upgrade::ROLLBACK_TIMELOCK);
+    env.ledger().with_mut(|li| li.timestamp = prop.execution_time);
+    client.execute_upgrade(&rollback, &executor);
+
+    assert_eq!(client.get_upgrade_history().len(), 1);
+    env.as_contract(&client.address, || {
+        assert_eq!(UpgradeManager::current_hash(&env), Some(symbol_short!("v2hash")));
+    });
+}
+
+#[test]
+fn test_rollback_checks_the_schema_each_upgrade_recorded() {
+    use shared::upgrade::RollbackEvent;
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let upgrade_to = |hash: Symbol| {
+        let id = client.propose_upgrade(&admin, &hash, &symbol_short!("Upgrade"));
+        client.approve_upgrade(&id, &approver);
+        pass_timelock(&env, &client, id);
+        client.execute_upgrade(&id, &executor);
+    };
+    let roll_back = || {
+        let id = client.propose_rollback(&admin, &symbol_short!("undo"));
+        client.approve_upgrade(&id, &approver);
+        id
+    };
+
+    // v2 and v3 run on schema 1; v3 then migrates to schema 2 and v4 ships on it
+    upgrade_to(symbol_short!("v2hash"));
+    upgrade_to(symbol_short!("v3hash"));
+    env.as_contract(&client.address, || UpgradeManager::set_schema_version(&env, 2));
+    upgrade_to(symbol_short!("v4hash"));
+    let history = client.get_upgrade_history();
+    assert_eq!(history.get(1).unwrap().schema_version, 1);
+    assert_eq!(history.get(2).unwrap().schema_version, 2);
+
+    // v3 already read schema 2, so undoing v4 is allowed
+    let id = roll_back();
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+    let rollback_topic: soroban_sdk::Vec<soroban_sdk::Val> = (symbol_short!("rollback"),).into_val(&env);
+    let (_, _, data) = env.events().all().iter().find(|(_, topics, _)| *topics == rollback_topic).unwrap();
+    let event: RollbackEvent = data.into_val(&env);
+    assert_eq!(event.from_hash, symbol_short!("v4hash"));
+    assert_eq!(event.to_hash, symbol_short!("v3hash"));
+    assert_eq!(event.schema_version, 2);
+
+    // v2 only knows schema 1
+    assert_eq!(client.get_schema_version(), 2);
+    assert_eq!(client.try_propose_rollback(&admin, &symbol_short!("undo")), Err(Ok(GovernanceError::SchemaAhead)));
+
+    // A migration that lands while an approved rollback waits out its timelock blocks execution
+    env.as_contract(&client.address, || UpgradeManager::set_schema_version(&env, 1));
+    let id = roll_back();
+    env.as_contract(&client.address, || UpgradeManager::set_schema_version(&env, 2));
+    pass_timelock(&env, &client, id);
+    assert_eq!(client.try_execute_upgrade(&id, &executor), Err(Ok(GovernanceError::ActionFailed)));
+    env.as_contract(&client.address, || {
+        assert_eq!(UpgradeManager::current_hash(&env), Some(symbol_short!("v3hash")));
+    });
+    assert_eq!(client.get_upgrade_history().len(), 2);
+}
+
+#[test]
+fn test_delegate_approves_once_for_delegator_seat() {
+    let env = Env::default();
+    let (client, admin, members, _executor) = setup_council(&env);
+    let stand_in = Address::generate(&env);
+
+    let proposal_id = client.propose_upgrade(&admin, &symbol_short!("v2hash"), &symbol_short!("Upgrade"));
+
+    // No delegation yet
+    assert_eq!(client.try_approve_as_delegate(&proposal_id, &stand_in, &members[0]), Err(Ok(GovernanceError::DelegationNotActive)));
+
+    client.delegate_approval(&members[0], &stand_in, &2000);
+    client.approve_as_delegate(&proposal_id, &stand_in, &members[0]);
+
+    // The seat has voted: neither the delegate nor the delegator can add a second approval
+    assert_eq!(client.try_approve_as_delegate(&proposal_id, &stand_in, &members[0]), Err(Ok(GovernanceError::DuplicateApproval)));
+    assert_eq!(client.try_approve_upgrade(&proposal_id, &members[0]), Err(Ok(GovernanceError::DuplicateApproval)));
+    let prop = client.get_upgrade_proposal(&proposal_id);
+    assert_eq!(prop.approvals_count, 1);
+    assert_eq!(prop.status, ProposalStatus::Pending);
+
+    // Revoked delegations stop working immediately
+    let next_id = client.propose_upgrade(&admin, &symbol_short!("v3hash"), &symbol_short!("Upgrade"));
+    client.revoke_delegation(&members[0]);
+    assert_eq!(client.try_approve_as_delegate(&next_id, &stand_in, &members[0]), Err(Ok(GovernanceError::DelegationNotActive)));
+
+    // Lapsed delegations expire on their own
+    client.delegate_approval(&members[1], &stand_in, &1500);
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    assert!(client.get_delegation(&members[1]).is_none());
+    assert_eq!(client.try_approve_as_delegate(&next_id, &stand_in, &members[1]), Err(Ok(GovernanceError::DelegationNotActive)));
+}
+
+/// Attestation registry that approves a single trader
+#[contract]
+pub struct MockAttestationRegistry;
+
+#[contractimpl]
+impl MockAttestationRegistry {
+    pub fn attest(env: Env, trader: Address) {
+        env.storage().instance().set(&trader, &true);
+    }
+
+    pub fn is_eligible(env: Env, trader: Address, _pair: Symbol) -> bool {
+        env.storage().instance().get(&trader).unwrap_or(false)
+    }
+}
+
+/// Attestation registry that fails every lookup
+mod broken_registry {
+    use soroban_sdk::{contract, contracterror, contractimpl, Address, Env, Symbol};
+
+    #[contracterror]
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    #[repr(u32)]
+    pub enum RegistryError {
+        Offline = 1,
+    }
+
+    #[contract]
+    pub struct BrokenAttestationRegistry;
+
+    #[contractimpl]
+    impl BrokenAttestationRegistry {
+        pub fn is_eligible(_env: Env, _trader: Address, _pair: Symbol) -> Result<bool, RegistryError> {
+            Err(RegistryError::Offline)
+        }
+    }
+}
+use broken_registry::BrokenAttestationRegistry;
+
+#[test]
+fn test_trade_eligibility_per_pair() {
+    use crate::compliance::{AllowlistEvent, EligibilityMode};
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let officer = Address::generate(&env);
+    let trader = Address::generate(&env);
+    let other = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+    let pair = symbol_short!("XLMUSDC");
+
+    // Open by default
+    assert_eq!(client.get_eligibility(&pair), EligibilityMode::Open);
+    client.trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin);
+
+    assert_eq!(
+        client.try_set_eligibility(&officer, &pair, &EligibilityMode::Allowlist),
+        Err(Ok(TradeError::Unauthorized))
+    );
+    client.set_eligibility(&admin, &pair, &EligibilityMode::Allowlist);
+    assert_eq!(
+        client.try_trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::TraderNotEligible))
+    );
+
+    // Only the compliance role keeps the allowlist
+    let traders = soroban_sdk::vec![&env, trader.clone(), other.clone()];
+    assert_eq!(client.try_add_to_allowlist(&officer, &pair, &traders), Err(Ok(TradeError::Unauthorized)));
+    let id = client.grant_role(&admin, &officer, &GovernanceRole::Compliance);
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    client.add_to_allowlist(&officer, &pair, &traders);
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("allow_add"),).into_val(&env));
+    let event: AllowlistEvent = data.into_val(&env);
+    assert_eq!((event.pair, event.traders, event.updated_by), (pair.clone(), traders, officer.clone()));
+
+    client.trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin);
+    client.trade(&other, &pair, &10, &100, &true, &fee_token, &0, &admin);
+
+    client.remove_from_allowlist(&officer, &pair, &soroban_sdk::vec![&env, other.clone()]);
+    let (_, topics, _) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("allow_rm"),).into_val(&env));
+    assert!(client.is_allowlisted(&pair, &trader));
+    assert!(!client.is_allowlisted(&pair, &other));
+    assert_eq!(
+        client.try_trade(&other, &pair, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::TraderNotEligible))
+    );
+
+    // Attestation defers to the registry; a registry that can't answer blocks the trade
+    let registry = env.register_contract(None, MockAttestationRegistry);
+    MockAttestationRegistryClient::new(&env, &registry).attest(&other);
+    client.set_eligibility(&admin, &pair, &EligibilityMode::Attestation(registry));
+    client.trade(&other, &pair, &10, &100, &true, &fee_token, &0, &admin);
+    assert_eq!(
+        client.try_trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::TraderNotEligible))
+    );
+
+    let broken = env.register_contract(None, BrokenAttestationRegistry);
+    client.set_eligibility(&admin, &pair, &EligibilityMode::Attestation(broken));
+    assert_eq!(
+        client.try_trade(&other, &pair, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::AttestationUnavailable))
+    );
+}
+
+#[test]
+fn test_error_codes_fall_in_registered_blocks() {
+    use shared::errors::{source_of, ErrorSource};
+
+    assert_eq!(source_of(FeeError::InvalidAmount as u32), Some(ErrorSource::Fee));
+    assert_eq!(source_of(GovernanceError::Unauthorized as u32), Some(ErrorSource::Governance));
+    assert_eq!(source_of(TradeError::Unauthorized as u32), Some(ErrorSource::Trade));
+    assert_eq!(source_of(shared::safe_call::errors::CALL_FAILED), Some(ErrorSource::SafeCall));
+    assert_eq!(source_of(3000), None);
+}
+
+/// Voting token whose `balance_at` returns the wrong type
+#[contract]
+pub struct MisdeclaredVotingToken;
+
+#[contractimpl]
+impl MisdeclaredVotingToken {
+    pub fn balance_at(_env: Env, _account: Address, _ledger: u32) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_voting_power_read_failures_keep_their_cause() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let token = env.register_contract(None, MisdeclaredVotingToken);
+
+    let config = VotingConfig {
+        token: token.clone(),
+        quorum: 1,
+        pass_threshold_bps: 5_000,
+        voting_period: 3_600,
+        proposal_threshold: 0,
+    };
+    let id = client.propose_action(&admin, &ProposalAction::SetVotingConfig(config), &symbol_short!("vote"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    let action = ProposalAction::Unpause(PauseScope::Global);
+    assert_eq!(
+        client.try_propose_token_vote(&admin, &action, &symbol_short!("vote")),
+        Err(Ok(GovernanceError::VotingTokenDecode))
+    );
+
+    // Once trusted targets are required, the unregistered token isn't called at all
+    let id = client.propose_action(&admin, &ProposalAction::RequireTrustedTargets(true), &symbol_short!("trust"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+    assert_eq!(
+        client.try_propose_token_vote(&admin, &action, &symbol_short!("vote")),
+        Err(Ok(GovernanceError::VotingTokenUntrusted))
+    );
+}
+
+/// Voting token with a fixed balance per account (own module so its
+/// `balance_at` doesn't clash with `MisdeclaredVotingToken`'s)
+mod voting_token {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MockVotingToken;
+
+    #[contractimpl]
+    impl MockVotingToken {
+        pub fn set_balance(env: Env, account: Address, balance: i128) {
+            env.storage().instance().set(&account, &balance);
+        }
+
+        pub fn balance_at(env: Env, account: Address, _ledger: u32) -> i128 {
+            env.storage().instance().get(&account).unwrap_or(0)
+        }
+    }
+}
+use voting_token::{MockVotingToken, MockVotingTokenClient};
+
+#[test]
+fn test_token_vote_keeps_the_terms_it_opened_under() {
+    use shared::voting::{VoteCastEvent, VoteChoice, VoteTerms};
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let token = env.register_contract(None, MockVotingToken);
+    let voter = Address::generate(&env);
+    MockVotingTokenClient::new(&env, &token).set_balance(&voter, &100);
+
+    let config = VotingConfig {
+        token: token.clone(),
+        quorum: 100,
+        pass_threshold_bps: 5_000,
+        voting_period: 172_800,
+        proposal_threshold: 0,
+    };
+    client.configure_token_voting(&admin, &config);
+
+    let action = ProposalAction::Unpause(PauseScope::Global);
+    let vote_id = client.propose_token_vote(&admin, &action, &symbol_short!("vote"));
+    assert_eq!(
+        client.get_vote_terms(&vote_id),
+        VoteTerms { token, quorum: 100, pass_threshold_bps: 5_000 }
+    );
+
+    // Raise the bar and swap to a token the voter holds none of while the vote is open
+    let new_token = env.register_contract(None, MockVotingToken);
+    let stricter = VotingConfig {
+        token: new_token.clone(),
+        quorum: 1_000,
+        pass_threshold_bps: 9_000,
+        ..config
+    };
+    let id = client.propose_action(&admin, &ProposalAction::SetVotingConfig(stricter), &symbol_short!("vcfg"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    client.cast_vote(&vote_id, &voter, &VoteChoice::For, &symbol_short!("lgtm"));
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("vote"),).into_val(&env));
+    let event: VoteCastEvent = data.into_val(&env);
+    assert_eq!(event.proposal_id, vote_id);
+    assert_eq!(event.voter, voter);
+    assert_eq!(event.vote_type, symbol_short!("for"));
+    assert_eq!(event.voting_power, 100);
+    assert_eq!(event.reason, symbol_short!("lgtm"));
+
+    // Weighed by the token and decided by the quorum it opened with, not the new ones
+    let deadline = client.get_upgrade_proposal(&vote_id).voting_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    assert_eq!(client.finalize_vote(&vote_id), ProposalStatus::Approved);
+
+    let next_id = client.propose_token_vote(&admin, &action, &symbol_short!("vote"));
+    assert_eq!(
+        client.get_vote_terms(&next_id),
+        VoteTerms { token: new_token, quorum: 1_000, pass_threshold_bps: 9_000 }
+    );
+}
+
+#[test]
+fn test_late_finalized_token_vote_still_waits_its_timelock() {
+    use shared::voting::VoteChoice;
+
+    let env = Env::default();
+    let (client, admin, _approver, executor) = setup_governance(&env);
+    let token = env.register_contract(None, MockVotingToken);
+    let voter = Address::generate(&env);
+    MockVotingTokenClient::new(&env, &token).set_balance(&voter, &100);
+
+    let config = VotingConfig {
+        token,
+        quorum: 100,
+        pass_threshold_bps: 5_000,
+        voting_period: 172_800,
+        proposal_threshold: 0,
+    };
+    client.configure_token_voting(&admin, &config);
+
+    let action = ProposalAction::Pause(PauseScope::Global, 3_600);
+    let vote_id = client.propose_token_vote(&admin, &action, &symbol_short!("vote"));
+    assert_eq!(client.get_upgrade_proposal(&vote_id).execution_time, 0);
+    client.cast_vote(&vote_id, &voter, &VoteChoice::For, &symbol_short!("lgtm"));
+
+    // Nobody finalizes until well after deadline + timelock
+    let deadline = client.get_upgrade_proposal(&vote_id).voting_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 3 * DEFAULT_OPS_TIMELOCK);
+    assert_eq!(client.finalize_vote(&vote_id), ProposalStatus::Approved);
+
+    let finalized_at = env.ledger().timestamp();
+    assert_eq!(client.get_upgrade_proposal(&vote_id).execution_time, finalized_at + DEFAULT_OPS_TIMELOCK);
+    assert_eq!(client.try_execute_upgrade(&vote_id, &executor), Err(Ok(GovernanceError::TimelockNotExpired)));
+
+    env.ledger().with_mut(|li| li.timestamp = finalized_at + DEFAULT_OPS_TIMELOCK);
+    client.execute_upgrade(&vote_id, &executor);
+    assert!(client.get_pause(&PauseScope::Global).is_some());
+}
+
+#[test]
+fn test_safe_invoke_reports_why_a_call_failed() {
+    use shared::safe_call::{safe_invoke_typed, SafeCallError};
+    use soroban_sdk::IntoVal;
+
+    let env = Env::default();
+    let (client, _admin, _approver, _executor) = setup_governance(&env);
+    let caller = env.register_contract(None, MockRewardContract);
+    let no_args = Vec::new(&env);
+
+    env.as_contract(&caller, || {
+        let version = safe_invoke_typed::<u32>(&env, &client.address, &Symbol::new(&env, "get_version"), no_args.clone());
+        assert_eq!(version, Ok(1));
+
+        // Wrong return type
+        let decoded = safe_invoke_typed::<bool>(&env, &client.address, &Symbol::new(&env, "get_version"), no_args.clone());
+        assert_eq!(decoded, Err(SafeCallError::Decode));
+
+        // The callee's own error code comes back intact
+        let args = soroban_sdk::vec![&env, 999u64.into_val(&env)];
+        let missing = safe_invoke_typed::<UpgradeProposal>(&env, &client.address, &Symbol::new(&env, "get_upgrade_proposal"), args);
+        assert_eq!(missing, Err(SafeCallError::Contract(GovernanceError::ProposalNotFound as u32)));
+
+        // Native test contracts report a missing function as a generic context
+        // error rather than the `MissingValue` deployed WASM raises
+        let unknown = safe_invoke_typed::<u32>(&env, &client.address, &Symbol::new(&env, "no_such_fn"), no_args.clone());
+        assert_eq!(unknown, Err(SafeCallError::Aborted));
+
+        // A callee that panics isn't exercised here: the SDK's native test
+        // contracts enter through `extern "C"` functions, and a panic crossing
+        // one aborts the test process
+    });
+}
+
+#[test]
+fn test_registry_enforcement_refuses_unregistered_targets() {
+    use shared::safe_call::{safe_invoke_typed, SafeCallError};
+
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let target = env.register_contract(None, UpgradeableTradingContract);
+    let func = Symbol::new(&env, "get_version");
+
+    for action in [
+        ProposalAction::RequireTrustedTargets(true),
+        ProposalAction::TrustTarget(target.clone()),
+    ] {
+        let id = client.propose_action(&admin, &action, &symbol_short!("trust"));
+        client.approve_upgrade(&id, &approver);
+        pass_timelock(&env, &client, id);
+        client.execute_upgrade(&id, &executor);
+    }
+    assert!(client.requires_trusted_targets());
+    assert_eq!(client.get_trusted_target(&target).unwrap().registered_at, env.ledger().timestamp());
+
+    let unregistered = env.register_contract(None, UpgradeableTradingContract);
+    env.as_contract(&client.address, || {
+        // Registered targets are called; unregistered ones are refused before the call
+        assert!(safe_invoke_typed::<u32>(&env, &target, &func, Vec::new(&env)).is_ok());
+        assert_eq!(
+            safe_invoke_typed::<u32>(&env, &unregistered, &func, Vec::new(&env)),
+            Err(SafeCallError::Untrusted)
+        );
+    });
+
+    let id = client.propose_action(&admin, &ProposalAction::UntrustTarget(target.clone()), &symbol_short!("trust"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+    assert_eq!(client.get_trusted_target(&target), None);
+}
+
+#[test]
+fn test_reentrancy_guard_releases_on_drop() {
+    use shared::guard::{non_reentrant, GuardError, ReentrancyGuard};
+
+    let env = Env::default();
+    let (client, _admin, _approver, _executor) = setup_governance(&env);
+
+    env.as_contract(&client.address, || {
+        let guard = ReentrancyGuard::enter(&env, symbol_short!("trade")).unwrap();
+        assert!(ReentrancyGuard::is_entered(&env, symbol_short!("trade")));
+        assert_eq!(ReentrancyGuard::enter(&env, symbol_short!("trade")).err(), Some(GuardError::Reentered));
+
+        // Other scopes are independent
+        assert!(ReentrancyGuard::enter(&env, symbol_short!("fees")).is_ok());
+        drop(guard);
+        assert!(!ReentrancyGuard::is_entered(&env, symbol_short!("trade")));
+
+        // An early error return still releases the lock
+        let failed: Result<(), TradeError> =
+            non_reentrant(&env, symbol_short!("trade"), || Err(TradeError::InvalidAmount));
+        assert_eq!(failed, Err(TradeError::InvalidAmount));
+        assert!(!ReentrancyGuard::is_entered(&env, symbol_short!("trade")));
+    });
+}
+
+#[test]
+fn test_malicious_fee_token_cannot_reenter_trade() {
+    let env = Env::default();
+    let (client, _admin, _approver, _executor) = setup_governance(&env);
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let trader = Address::generate(&env);
+    let token_id = env.register_contract(None, ReentrantToken);
+    let token = ReentrantTokenClient::new(&env, &token_id);
+    token.set_target(&client.address, &trader);
+
+    let trade_id = client.trade(&trader, &symbol_short!("XLMUSDC"), &10, &100, &true, &token_id, &5, &trader);
+    assert_eq!(trade_id, 1);
+
+    // The nested trade from inside transfer() was refused; only the outer one is recorded.
+    // The host itself refuses a contract re-entering itself, so this holds with or
+    // without the guard; the test below covers the guard's own refusal
+    assert!(!token.reentered());
+    assert_eq!(client.get_stats().total_trades, 1);
+}
+
+#[test]
+fn test_held_locks_refuse_trade_with_guard_error() {
+    use shared::guard::ReentrancyGuard;
+
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let trader = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+    let pair = symbol_short!("XLMUSDC");
+
+    // A lock left held, as it is for the duration of an outer trade or fee
+    // transfer, refuses the entry point with the guard's error
+    for scope in [symbol_short!("trade"), symbol_short!("fees")] {
+        env.as_contract(&client.address, || {
+            core::mem::forget(ReentrancyGuard::enter(&env, scope.clone()).unwrap());
+        });
+        assert_eq!(
+            client.try_trade(&trader, &pair, &10, &100, &true, &fee_token, &5, &admin),
+            Err(Ok(TradeError::Reentered))
+        );
+
+        env.as_contract(&client.address, || {
+            env.storage().instance().remove(&(symbol_short!("lock"), scope.clone()));
+        });
+    }
+
+    assert_eq!(TradeError::Reentered as u32, 3022);
+    assert_eq!(client.get_stats().total_trades, 0);
+    client.trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin);
+    assert_eq!(client.get_stats().total_trades, 1);
+}
+
+#[test]
+fn test_candles_roll_over_intervals_and_page() {
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let trader = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+    let pair = symbol_short!("XLMUSDC");
+
+    // Intervals must be non-zero, distinct and at most MAX_CANDLE_INTERVALS
+    for intervals in [
+        soroban_sdk::vec![&env],
+        soroban_sdk::vec![&env, 60u64, 0],
+        soroban_sdk::vec![&env, 60u64, 3_600, 60],
+        soroban_sdk::vec![&env, 60u64, 300, 900, 3_600, 86_400],
+    ] {
+        assert_eq!(
+            client.try_set_candle_intervals(&admin, &pair, &intervals),
+            Err(Ok(TradeError::InvalidInterval))
+        );
+    }
+    client.set_candle_intervals(&admin, &pair, &soroban_sdk::vec![&env, 60u64, 3_600]);
+    assert_eq!(client.get_candle_intervals(&pair), soroban_sdk::vec![&env, 60u64, 3_600]);
+    assert_eq!(client.try_get_candles(&pair, &86_400, &0, &10), Err(Ok(TradeError::InvalidInterval)));
+
+    // Three trades in the minute starting at 960, then one in each of the next two minutes
+    for (at, price, amount) in [(1_000, 100, 10), (1_010, 120, 5), (1_015, 90, 1), (1_020, 110, 2), (1_100, 105, 3)] {
+        env.ledger().with_mut(|li| li.timestamp = at);
+        client.trade(&trader, &pair, &amount, &price, &true, &fee_token, &0, &admin);
+    }
+
+    let candle = |open_time, open, high, low, close, volume, trades| Candle {
+        open_time,
+        open,
+        high,
+        low,
+        close,
+        volume,
+        trades,
+    };
+    let minutes = client.get_candles(&pair, &60, &0, &10);
+    assert_eq!(
+        minutes,
+        soroban_sdk::vec![
+            &env,
+            candle(960, 100, 120, 90, 90, 16, 3),
+            candle(1_020, 110, 110, 110, 110, 2, 1),
+            candle(1_080, 105, 105, 105, 105, 3, 1),
+        ]
+    );
+    assert_eq!(
+        client.get_candles(&pair, &3_600, &0, &10),
+        soroban_sdk::vec![&env, candle(0, 100, 120, 90, 105, 21, 5)]
+    );
+
+    // `from` picks the bucket containing it; `limit` caps the page
+    assert_eq!(client.get_candles(&pair, &60, &0, &2), minutes.slice(0..2));
+    assert_eq!(client.get_candles(&pair, &60, &1_030, &10), minutes.slice(1..3));
+    assert_eq!(client.get_candles(&pair, &60, &1_139, &1), minutes.slice(2..3));
+    assert_eq!(client.get_candles(&pair, &60, &1_140, &10).len(), 0);
+    assert_eq!(client.get_candles(&pair, &60, &0, &0).len(), 0);
+}
+
+#[test]
+fn test_trade_inputs_and_price_accumulator_are_checked() {
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let trader = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+    let pair = symbol_short!("XLMUSDC");
+
+    for (amount, price) in [(0, 100), (-5, 100), (10, 0), (10, -1)] {
+        assert_eq!(
+            client.try_trade(&trader, &pair, &amount, &price, &true, &fee_token, &0, &admin),
+            Err(Ok(TradeError::InvalidAmount))
+        );
+    }
+
+    // 100 for 30s, then 200 for 30s
+    client.trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    client.trade(&trader, &pair, &10, &200, &true, &fee_token, &0, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 30);
+    assert_eq!(client.get_twap(&pair, &60), 150);
+    assert_eq!(client.try_get_twap(&pair, &120), Err(Ok(TradeError::InsufficientHistory)));
+
+    // A price that overflows the accumulator is refused rather than wrapping
+    let other = symbol_short!("BTCUSDC");
+    client.trade(&trader, &other, &10, &(i128::MAX / 2), &true, &fee_token, &0, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 10);
+    assert_eq!(
+        client.try_trade(&trader, &other, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::ArithmeticOverflow))
+    );
+    assert_eq!(client.try_get_twap(&other, &5), Err(Ok(TradeError::ArithmeticOverflow)));
+
+    // So is volume that would overflow the running totals
+    assert_eq!(
+        client.try_trade(&trader, &symbol_short!("ETHUSDC"), &i128::MAX, &1, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::ArithmeticOverflow))
+    );
+    assert_eq!(client.get_stats().total_trades, 3);
+}
+
+fn order(pair: &Symbol, amount: i128, limit_price: i128, is_buy: bool) -> OrderRequest {
+    OrderRequest {
+        pair: pair.clone(),
+        amount,
+        limit_price,
+        is_buy,
+    }
+}
+
+#[test]
+fn test_auction_orders_are_bounded_and_pay_fees() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let pair = symbol_short!("XLMUSDC");
+    client.set_auction_mode(&admin, &pair, &60);
+
+    let token_id = env.register_stellar_asset_contract(Address::generate(&env));
+    let token_admin = token::StellarAssetClient::new(&env, &token_id);
+    let treasury = Address::generate(&env);
+    let (seller, buyer, other, spammer) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    for trader in [&seller, &buyer, &other] {
+        token_admin.mint(trader, &100);
+    }
+
+    let spec = ParamSpec { min: 1, max: 1_000_000, change_delay: 0 };
+    let id = client.propose_action(&admin, &ProposalAction::DefineParam(AUCTION_MIN_ORDER_PARAM, spec, 10), &symbol_short!("auct"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    assert_eq!(
+        client.try_place_order(&seller, &order(&pair, 5, 100, false), &token_id, &10, &treasury),
+        Err(Ok(TradeError::OrderTooSmall))
+    );
+    let auction_id = client.place_order(&seller, &order(&pair, 30, 100, false), &token_id, &10, &treasury);
+    client.place_order(&buyer, &order(&pair, 20, 110, true), &token_id, &10, &treasury);
+    client.place_order(&other, &order(&pair, 20, 100, true), &token_id, &10, &treasury);
+
+    // Placement fees are collected like trade() fees
+    assert_eq!(token::Client::new(&env, &token_id).balance(&treasury), 30);
+    assert_eq!(token::Client::new(&env, &token_id).balance(&seller), 90);
+
+    // One account can't fill the book with resting orders
+    for _ in 0..MAX_ORDERS_PER_TRADER {
+        client.place_order(&spammer, &order(&pair, 10, 1, true), &token_id, &0, &treasury);
+    }
+    assert_eq!(
+        client.try_place_order(&spammer, &order(&pair, 10, 1, true), &token_id, &0, &treasury),
+        Err(Ok(TradeError::TraderOrderLimit))
+    );
+
+    // Orders the pro-rata math couldn't settle are refused up front
+    assert_eq!(
+        client.try_place_order(&seller, &order(&pair, 1i128 << 64, 100, false), &token_id, &0, &treasury),
+        Err(Ok(TradeError::ArithmeticOverflow))
+    );
+
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let result = client.settle_auction(&pair);
+    assert_eq!(result.auction_id, auction_id);
+    assert_eq!((result.clearing_price, result.volume, result.fills), (100, 30, 3));
+    assert_eq!(client.get_stats().total_volume, 60);
+}
+
+#[test]
+fn test_auction_splits_the_marginal_level_pro_rata() {
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let pair = symbol_short!("XLMUSDC");
+    let fee_token = Address::generate(&env);
+    client.set_auction_mode(&admin, &pair, &60);
+
+    // Continuous trading is off while the pair runs auctions
+    let trader = Address::generate(&env);
+    assert_eq!(
+        client.try_trade(&trader, &pair, &10, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::AuctionModeActive))
+    );
+
+    // Three sellers share the 100 level, placed in time order, then a better-priced one
+    let sellers = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    for seller in sellers.iter() {
+        client.place_order(seller, &order(&pair, 10, 100, false), &fee_token, &0, &admin);
+    }
+    let cheap = Address::generate(&env);
+    client.place_order(&cheap, &order(&pair, 5, 90, false), &fee_token, &0, &admin);
+    let buyer = Address::generate(&env);
+    client.place_order(&buyer, &order(&pair, 15, 100, true), &fee_token, &0, &admin);
+
+    assert_eq!(client.try_settle_auction(&pair), Err(Ok(TradeError::AuctionNotClosed)));
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let result = client.settle_auction(&pair);
+    assert_eq!((result.clearing_price, result.volume, result.fills), (100, 15, 5));
+    assert_eq!(client.get_auction(&pair), None);
+
+    // The 90 seller fills first; the 10 left split 3/3/3 with the spare unit to the earliest
+    let trades: Vec<Trade> = env.as_contract(&client.address, || {
+        env.storage().persistent().get(&symbol_short!("trades")).unwrap()
+    });
+    let mut fills: Vec<(Address, i128)> = Vec::new(&env);
+    for trade in trades.iter() {
+        assert_eq!(trade.price, 100);
+        fills.push_back((trade.trader, trade.amount));
+    }
+    assert_eq!(
+        fills,
+        soroban_sdk::vec![
+            &env,
+            (sellers[0].clone(), 4),
+            (sellers[1].clone(), 3),
+            (sellers[2].clone(), 3),
+            (cheap, 5),
+            (buyer, 15),
+        ]
+    );
+}
+
+#[test]
+fn test_auction_book_limits_and_uncrossed_books() {
+    let env = Env::default();
+    let (client, admin, _approver, _executor) = setup_governance(&env);
+    let pair = symbol_short!("XLMUSDC");
+    let fee_token = Address::generate(&env);
+    client.set_auction_mode(&admin, &pair, &60);
+
+    // Bids below every ask: the round closes with nothing matched
+    client.place_order(&Address::generate(&env), &order(&pair, 10, 90, true), &fee_token, &0, &admin);
+    client.place_order(&Address::generate(&env), &order(&pair, 10, 100, false), &fee_token, &0, &admin);
+    env.ledger().with_mut(|li| li.timestamp += 60);
+    let result = client.settle_auction(&pair);
+    assert_eq!((result.volume, result.fills), (0, 0));
+    assert_eq!(client.get_stats().total_trades, 0);
+    assert_eq!(client.get_auction(&pair), None);
+
+    // The book holds MAX_AUCTION_ORDERS, however many traders share it
+    let mut placed = 0;
+    while placed < MAX_AUCTION_ORDERS {
+        let trader = Address::generate(&env);
+        for _ in 0..MAX_ORDERS_PER_TRADER {
+            client.place_order(&trader, &order(&pair, 1, 100, true), &fee_token, &0, &admin);
+            placed += 1;
+        }
+    }
+    assert_eq!(
+        client.try_place_order(&Address::generate(&env), &order(&pair, 1, 100, true), &fee_token, &0, &admin),
+        Err(Ok(TradeError::AuctionFull))
+    );
+}
+
+#[test]
+fn test_trade_volume_is_rate_limited_per_trader() {
+    let env = Env::default();
+    let (client, admin, approver, executor) = setup_governance(&env);
+    let trader = Address::generate(&env);
+    let other = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+    let pair = symbol_short!("XLMUSDC");
+
+    // Unlimited until governance defines the cap
+    assert_eq!(client.get_volume_allowance(&trader), None);
+
+    // The window is defined at init and can't outgrow what temporary storage can hold
+    let window = ParamSpec { min: 1, max: 63_072_000, change_delay: 0 };
+    assert_eq!(
+        client.try_propose_action(
+            &admin,
+            &ProposalAction::DefineParam(VOLUME_WINDOW_PARAM, window, 63_072_000),
+            &symbol_short!("vol"),
+        ),
+        Err(Ok(GovernanceError::InvalidAction))
+    );
+    assert_eq!(
+        client.try_propose_action(
+            &admin,
+            &ProposalAction::SetParam(VOLUME_WINDOW_PARAM, MAX_WINDOW as i128 + 1),
+            &symbol_short!("vol"),
+        ),
+        Err(Ok(GovernanceError::ParamOutOfBounds))
+    );
+
+    let spec = ParamSpec { min: 1, max: 1_000_000, change_delay: 0 };
+    let actions = soroban_sdk::vec![
+        &env,
+        ProposalAction::DefineParam(VOLUME_CAP_PARAM, spec, 1_000),
+        ProposalAction::SetParam(VOLUME_WINDOW_PARAM, 3_600),
+    ];
+    let id = client.propose_actions(&admin, &actions, &symbol_short!("vol"));
+    client.approve_upgrade(&id, &approver);
+    pass_timelock(&env, &client, id);
+    client.execute_upgrade(&id, &executor);
+
+    // A bucket's TTL is capped at what the network allows instead of aborting the trade
+    env.ledger().with_mut(|li| {
+        li.min_persistent_entry_ttl = 50;
+        li.max_entry_ttl = 100;
+    });
+
+    client.trade(&trader, &pair, &600, &100, &true, &fee_token, &0, &admin);
+    assert_eq!(client.get_volume_allowance(&trader), Some(400));
+    assert_eq!(
+        client.try_trade(&trader, &pair, &500, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::RateLimited))
+    );
+
+    // Allowances are per trader
+    client.trade(&other, &pair, &1_000, &100, &true, &fee_token, &0, &admin);
+
+    // A full window later the bucket is full again
+    env.ledger().with_mut(|li| li.timestamp += 3_600);
+    assert_eq!(client.get_volume_allowance(&trader), Some(1_000));
+    client.trade(&trader, &pair, &1_000, &100, &true, &fee_token, &0, &admin);
+
+    // Auction orders draw on the same allowance, so settlement can't exceed it
+    let auction_pair = symbol_short!("BTCUSDC");
+    client.set_auction_mode(&admin, &auction_pair, &60);
+    client.place_order(&other, &order(&auction_pair, 600, 100, true), &fee_token, &0, &admin);
+    assert_eq!(client.get_volume_allowance(&other), Some(400));
+    assert_eq!(
+        client.try_place_order(&other, &order(&auction_pair, 500, 100, true), &fee_token, &0, &admin),
+        Err(Ok(TradeError::RateLimited))
+    );
+    assert_eq!(
+        client.try_trade(&other, &pair, &500, &100, &true, &fee_token, &0, &admin),
+        Err(Ok(TradeError::RateLimited))
+    );
+}
+
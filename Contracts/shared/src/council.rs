@@ -0,0 +1,92 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Vec};
+
+use crate::governance::{GovernanceError, GovernanceRole};
+use crate::roles::RoleManager;
+
+/// Standing approver council; every council proposal is decided by it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Council {
+    pub members: Vec<Address>,
+    pub threshold: u32,                    // Approvals a proposal needs
+    pub veto_threshold: u32,               // Rejections that kill a proposal
+}
+
+/// Council change event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CouncilUpdatedEvent {
+    pub members: Vec<Address>,
+    pub threshold: u32,
+    pub veto_threshold: u32,
+    pub updated_at: u64,
+}
+
+pub struct CouncilManager;
+
+impl CouncilManager {
+    /// Current council (empty until initialized)
+    pub fn get(env: &Env) -> Council {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("council"))
+            .unwrap_or(Council {
+                members: Vec::new(env),
+                threshold: 0,
+                veto_threshold: 0,
+            })
+    }
+
+    /// Default approval and veto threshold for a council of `size` members: a simple majority
+    pub fn majority(size: u32) -> u32 {
+        size / 2 + 1
+    }
+
+    /// Check that a council has unique members and reachable thresholds
+    pub fn validate(council: &Council) -> Result<(), GovernanceError> {
+        let size = council.members.len();
+        if council.threshold == 0
+            || council.threshold > size
+            || council.veto_threshold == 0
+            || council.veto_threshold > size
+        {
+            return Err(GovernanceError::InvalidThreshold);
+        }
+
+        for (index, member) in council.members.iter().enumerate() {
+            if council.members.last_index_of(member) != Some(index as u32) {
+                return Err(GovernanceError::InvalidThreshold);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the council, moving the Approver role to match its membership
+    pub fn set(env: &Env, council: &Council) -> Result<(), GovernanceError> {
+        Self::validate(council)?;
+
+        for member in Self::get(env).members.iter() {
+            if !council.members.contains(&member) {
+                RoleManager::revoke(env, &member, &GovernanceRole::Approver);
+            }
+        }
+        for member in council.members.iter() {
+            RoleManager::grant(env, &member, &GovernanceRole::Approver);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("council"), council);
+
+        let event = CouncilUpdatedEvent {
+            members: council.members.clone(),
+            threshold: council.threshold,
+            veto_threshold: council.veto_threshold,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("council"),), event);
+
+        Ok(())
+    }
+}
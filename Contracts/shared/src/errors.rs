@@ -0,0 +1,72 @@
+//! Contract error code registry.
+//!
+//! Every error enum owns its own block of codes, so a code returned by any
+//! Stellara contract identifies where it came from. New error enums take the
+//! next free block; codes inside a block are never reused.
+
+/// Fee collection (`fees::FeeError`): 1001-1999
+pub const FEE_ERRORS: u32 = 1000;
+
+/// Governance (`governance::GovernanceError`): 2001-2999
+pub const GOVERNANCE_ERRORS: u32 = 2000;
+
+/// Trading contract (`TradeError`): 3001-3999
+pub const TRADE_ERRORS: u32 = 3000;
+
+/// Academy vesting contract (`VestingError`): 4001-4999
+pub const VESTING_ERRORS: u32 = 4000;
+
+/// Cross-contract calls (`safe_call::errors`): 5001-5999
+pub const SAFE_CALL_ERRORS: u32 = 5000;
+
+/// Academy rewards contract (`ContractError`): 6001-6999
+pub const ACADEMY_REWARDS_ERRORS: u32 = 6000;
+
+/// Reentrancy guard (`guard::GuardError`): 7001-7999
+pub const GUARD_ERRORS: u32 = 7000;
+
+/// Rate limiter (`ratelimit::RateLimitError`): 8001-8999
+pub const RATE_LIMIT_ERRORS: u32 = 8000;
+
+/// Size of each error block
+pub const ERROR_BLOCK: u32 = 1000;
+
+/// Which error enum a code belongs to
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorSource {
+    Fee,
+    Governance,
+    Trade,
+    Vesting,
+    SafeCall,
+    AcademyRewards,
+    Guard,
+    RateLimit,
+}
+
+/// Look up the error enum that owns `code`
+pub fn source_of(code: u32) -> Option<ErrorSource> {
+    // Block bases themselves are never error codes
+    if code.is_multiple_of(ERROR_BLOCK) {
+        return None;
+    }
+
+    match code - code % ERROR_BLOCK {
+        FEE_ERRORS => Some(ErrorSource::Fee),
+        GOVERNANCE_ERRORS => Some(ErrorSource::Governance),
+        TRADE_ERRORS => Some(ErrorSource::Trade),
+        VESTING_ERRORS => Some(ErrorSource::Vesting),
+        SAFE_CALL_ERRORS => Some(ErrorSource::SafeCall),
+        ACADEMY_REWARDS_ERRORS => Some(ErrorSource::AcademyRewards),
+        GUARD_ERRORS => Some(ErrorSource::Guard),
+        RATE_LIMIT_ERRORS => Some(ErrorSource::RateLimit),
+        _ => None,
+    }
+}
+
+// Standard error names
+pub const UNAUTHORIZED: &str = "UNAUTHORIZED";
+pub const NOT_FOUND: &str = "NOT_FOUND";
+pub const INVALID_AMOUNT: &str = "INVALID_AMOUNT";
+pub const PAUSED: &str = "PAUSED";
+pub const ALREADY_EXISTS: &str = "ALREADY_EXISTS";
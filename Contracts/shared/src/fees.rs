@@ -1,11 +1,23 @@
-use soroban_sdk::{contracterror, Address, Env, token};
+use soroban_sdk::{contracterror, symbol_short, Address, Env, token};
 
+use crate::guard::{GuardError, ReentrancyGuard};
+use crate::safe_call::verify_target;
+
+/// Fee error codes (`errors::FEE_ERRORS` block)
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum FeeError {
     InsufficientBalance = 1001,
     InvalidAmount = 1002,
+    UntrustedToken = 1003,
+    Reentered = 1004,
+}
+
+impl From<GuardError> for FeeError {
+    fn from(_: GuardError) -> Self {
+        FeeError::Reentered
+    }
 }
 
 pub struct FeeManager;
@@ -37,6 +49,14 @@ impl FeeManager {
             return Ok(());
         }
 
+        // The token comes from the caller, so it must pass the target registry
+        if !verify_target(env, token) {
+            return Err(FeeError::UntrustedToken);
+        }
+
+        // The token runs arbitrary code during balance() and transfer()
+        let _guard = ReentrancyGuard::enter(env, symbol_short!("fees"))?;
+
         let token_client = token::Client::new(env, token);
         
         // Check balance
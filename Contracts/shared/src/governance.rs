@@ -1,4 +1,69 @@
-use soroban_sdk::{contracttype, Address, Env, Vec, Symbol, symbol_short};
+use soroban_sdk::{
+    contracterror, contracttype, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Val, Vec,
+};
+
+use crate::council::{Council, CouncilManager};
+use crate::params::{ParamSpec, ParamStore};
+use crate::pause::{PauseManager, PauseScope, PauseState, MAX_PAUSE_DURATION};
+use crate::roles::RoleManager;
+use crate::safe_call::{safe_invoke, SafeCallError, TargetRegistry};
+use crate::upgrade::{UpgradeManager, ROLLBACK_TIMELOCK};
+use crate::voting::{TokenVoting, VotingConfig};
+
+/// How long a proposal can collect approvals: 7 days
+pub const VOTING_PERIOD: u64 = 604_800;
+
+/// How long an approved proposal stays executable after its timelock: 14 days
+pub const EXECUTION_WINDOW: u64 = 1_209_600;
+
+/// How long an emergency proposal can collect approvals: 1 day
+pub const EMERGENCY_VOTING_PERIOD: u64 = 86_400;
+
+/// Timelock on approved emergency proposals: none
+pub const EMERGENCY_TIMELOCK: u64 = 0;
+
+/// Minimum time between guardian vetoes: 7 days
+pub const VETO_COOLDOWN: u64 = 604_800;
+
+/// Longest an approver can hand their vote to a delegate: 90 days
+pub const MAX_DELEGATION_PERIOD: u64 = 7_776_000;
+
+/// Most actions a single proposal can carry
+pub const MAX_PROPOSAL_ACTIONS: u32 = 10;
+
+/// The only function an emergency `Call` may invoke, and only on a registered trusted target
+pub const EMERGENCY_CALL_SELECTOR: Symbol = symbol_short!("pause");
+
+/// Most proposal IDs a single `list_proposals` call will scan
+pub const MAX_PROPOSAL_SCAN: u32 = 100;
+
+/// Timelock parameter for upgrades
+pub const TIMELOCK_UPGRADE_PARAM: Symbol = symbol_short!("tl_upgr");
+
+/// Timelock parameter for role, role-admin and council changes
+pub const TIMELOCK_ROLES_PARAM: Symbol = symbol_short!("tl_role");
+
+/// Timelock parameter for parameter, voting-config and trusted-target changes
+pub const TIMELOCK_CONFIG_PARAM: Symbol = symbol_short!("tl_cfg");
+
+/// Timelock parameter for pauses, treasury transfers and external calls
+pub const TIMELOCK_OPS_PARAM: Symbol = symbol_short!("tl_ops");
+
+/// Floor under the timelock of every non-emergency proposal
+pub const MIN_TIMELOCK_PARAM: Symbol = symbol_short!("tl_min");
+
+/// Default timelocks (seconds) for the parameters above, also used while a key is undefined
+pub const DEFAULT_MIN_TIMELOCK: u64 = 3_600;
+pub const DEFAULT_UPGRADE_TIMELOCK: u64 = 86_400;
+pub const DEFAULT_ROLES_TIMELOCK: u64 = 86_400;
+pub const DEFAULT_CONFIG_TIMELOCK: u64 = 14_400;
+pub const DEFAULT_OPS_TIMELOCK: u64 = 14_400;
+
+/// Longest a timelock parameter can be set to: 30 days
+pub const MAX_TIMELOCK: u64 = 2_592_000;
+
+/// Change delay on timelock parameters, so a shortened timelock can't be used right away: 1 day
+pub const TIMELOCK_CHANGE_DELAY: u64 = 86_400;
 
 /// Upgrade proposal that must be approved via governance
 #[contracttype]
@@ -6,21 +71,223 @@ use soroban_sdk::{contracttype, Address, Env, Vec, Symbol, symbol_short};
 pub struct UpgradeProposal {
     pub id: u64,
     pub proposer: Address,
-    pub new_contract_hash: Symbol,
+    pub actions: Vec<ProposalAction>,      // Run in order, all or nothing
     pub target_contract: Address,
     pub description: Symbol,
     pub approval_threshold: u32,           // e.g., 2 of 3
     pub approvers: Vec<Address>,
     pub approvals_count: u32,
+    pub veto_threshold: u32,               // Rejections that kill the proposal
+    pub rejections_count: u32,
     pub status: ProposalStatus,
     pub created_at: u64,
-    pub execution_time: u64,               // Timelock: when it can be executed
+    pub execution_time: u64,               // Approval time + timelock; 0 until approved
+    pub timelock: u64,                     // Delay between approval and execution
+    pub voting_deadline: u64,              // Pending proposals expire after this
+    pub execution_window: u64,             // Approved proposals expire this long after execution_time
     pub executed: bool,
+    pub outcomes: Vec<ActionOutcome>,      // One per action once executed
+    pub mode: VotingMode,
+    pub snapshot_ledger: u32,              // Token votes: balances are read at this ledger
+    pub emergency: bool,                   // Fast-track: unanimous council, no timelock
+}
+
+/// Proposal as stored in the legacy `props` map, before typed actions.
+///
+/// Only read by `migrate_storage`; field names and order must not change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyUpgradeProposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub new_contract_hash: Symbol,
+    pub target_contract: Address,
+    pub description: Symbol,
+    pub approval_threshold: u32,
+    pub approvers: Vec<Address>,
+    pub approvals_count: u32,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    pub execution_time: u64,
+    pub executed: bool,
+}
+
+/// How a proposal is decided
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VotingMode {
+    /// M-of-N approvals from the council at proposal time
+    Council,
+    /// For/Against/Abstain votes weighted by checkpointed token balance
+    TokenVote,
+}
+
+/// What a proposal does once executed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalAction {
+    /// Move the target contract to a new code hash
+    Upgrade(Symbol),
+    /// Change a governance-managed parameter (within its bounds, after its change delay)
+    SetParam(Symbol, i128),
+    /// Register a new parameter: (key, bounds and change delay, starting value)
+    DefineParam(Symbol, ParamSpec, i128),
+    /// Go back to the code hash the last upgrade replaced (only via `propose_rollback`)
+    Rollback,
+    /// Give an address a role
+    GrantRole(Address, GovernanceRole),
+    /// Take a role away from an address
+    RevokeRole(Address, GovernanceRole),
+    /// Change which role may propose grants/revocations of a role: (role, admin role)
+    SetRoleAdmin(GovernanceRole, GovernanceRole),
+    /// Pause a scope for the given number of seconds
+    Pause(PauseScope, u64),
+    /// Push an active pause's expiry out by the given number of seconds
+    ExtendPause(PauseScope, u64),
+    /// Lift the pause on a scope
+    Unpause(PauseScope),
+    /// Send treasury funds: (token, recipient, amount)
+    TransferTreasury(Address, Address, i128),
+    /// Call another contract through `safe_invoke`: (contract, function, args)
+    Call(Address, Symbol, Vec<Val>),
+    /// Replace the token-voting configuration
+    SetVotingConfig(VotingConfig),
+    /// Replace the approver council and its threshold
+    SetCouncil(Council),
+    /// Register a trusted call target
+    TrustTarget(Address),
+    /// Remove a contract from the trusted target registry
+    UntrustTarget(Address),
+    /// Turn on (or off) refusing calls to unregistered targets
+    RequireTrustedTargets(bool),
+}
+
+/// A council member's recorded vote on a proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CouncilVote {
+    Approve,
+    Reject,
+}
+
+/// One entry of a proposal's per-approver vote record
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApproverVote {
+    pub approver: Address,
+    pub vote: CouncilVote,
+}
+
+/// A council member's off-chain approval, checked against their registered key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalSignature {
+    pub approver: Address,
+    pub signature: BytesN<64>,
+}
+
+/// An approver's vote handed to a stand-in until `until`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalDelegation {
+    pub delegate: Address,
+    pub until: u64,                        // Delegation lapses at this timestamp
+}
+
+/// Delegation change event for off-chain indexing (topics `delegate` / `undeleg`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DelegationEvent {
+    pub delegator: Address,
+    pub delegate: Address,
+    pub until: u64,
+    pub timestamp: u64,
+}
+
+/// Approval cast by a delegate for a council seat (topic `dlg_appr`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DelegatedApprovalEvent {
+    pub proposal_id: u64,
+    pub delegate: Address,                 // Who signed the approval
+    pub delegator: Address,                // Whose seat it counts for
+    pub timestamp: u64,
+}
+
+/// Result of running one of a proposal's actions.
+///
+/// A failing action aborts execution and rolls back every action before it
+/// (see `ActionFailedEvent`), so an executed proposal only ever records `Succeeded`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ActionOutcome {
+    Succeeded,
+}
+
+/// Failing action of a proposal, published just before execution reverts (topic `act_fail`).
+///
+/// The revert drops it from the ledger's event stream, but simulation and
+/// diagnostic events still carry it, so clients can see which action failed and why.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ActionFailedEvent {
+    pub proposal_id: u64,
+    pub index: u32,                        // Position of the action in the proposal
+    pub code: u32,                         // The action's own error code
+    pub timestamp: u64,
+}
+
+/// Expiry event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalExpiredEvent {
+    pub proposal_id: u64,
+    pub previous_status: ProposalStatus,
+    pub expired_at: u64,
+}
+
+/// Execution event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+    pub executor: Address,
+    pub outcomes: Vec<ActionOutcome>,
+    pub executed_at: u64,
+}
+
+/// Guardian veto event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalVetoedEvent {
+    pub proposal_id: u64,
+    pub guardian: Address,
+    pub vetoed_at: u64,
+}
+
+/// One page of `list_proposals` results
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ProposalPage {
+    pub proposals: Vec<UpgradeProposal>,
+    pub next_cursor: Option<u64>,          // Pass back as `cursor` to continue; None when done
+}
+
+/// Emergency proposal activity for off-chain indexing (topic `emergency`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EmergencyActionEvent {
+    pub action_id: u64,
+    pub council_member: Address,
+    pub action_type: Symbol,               // propose, approve, reject or execute
+    pub target_contract: Address,
+    pub description: Symbol,
+    pub timestamp: u64,
 }
 
 /// Status of an upgrade proposal
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum ProposalStatus {
     Pending = 0,
@@ -28,19 +295,24 @@ pub enum ProposalStatus {
     Rejected = 2,
     Executed = 3,
     Cancelled = 4,
+    Expired = 5,
+    Vetoed = 6,
 }
 
-/// Governance role
+/// Governance role; an address may hold several (see `roles::RoleManager`)
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum GovernanceRole {
     Admin = 0,        // Can propose upgrades and cancel
     Approver = 1,     // Can approve/reject proposals
     Executor = 2,     // Can execute approved proposals (after timelock)
+    Compliance = 3,   // Can maintain trader allowlists
+    Guardian = 4,     // Can pause (time-limited) and veto during timelock; cannot unpause
 }
 
-/// Governance error codes
+/// Governance error codes (`errors::GOVERNANCE_ERRORS` block)
+#[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum GovernanceError {
@@ -52,37 +324,61 @@ pub enum GovernanceError {
     InvalidThreshold = 2006,
     DuplicateApproval = 2007,
     ProposalNotFound = 2008,
+    LastAdmin = 2009,
+    RoleNotHeld = 2010,
+    TransferFailed = 2011,
+    InvalidAction = 2012,
+    ProposalExpired = 2013,
+    VotingNotConfigured = 2014,
+    VotingClosed = 2015,
+    VotingStillOpen = 2016,
+    AlreadyVoted = 2017,
+    InsufficientVotingPower = 2018,
+    ApprovalNotFound = 2019,
+    ActionFailed = 2020,
+    SigningKeyNotSet = 2021,
+    VetoRateLimited = 2022,
+    UnknownParam = 2023,
+    ParamOutOfBounds = 2024,
+    ParamChangePending = 2025,
+    NoPreviousVersion = 2026,
+    SchemaAhead = 2027,
+    DelegationNotActive = 2028,
+    VotingTokenError = 2029,
+    VotingTokenAuth = 2030,
+    VotingTokenNotFound = 2031,
+    VotingTokenBudget = 2032,
+    VotingTokenDecode = 2033,
+    VotingTokenAborted = 2034,
+    VotingTokenUntrusted = 2035,
+    NotPaused = 2036,
 }
 
-impl From<GovernanceError> for soroban_sdk::Error {
-    fn from(error: GovernanceError) -> Self {
-        soroban_sdk::Error::from_contract_error(error as u32)
+/// Failed voting-token reads keep their cause
+impl From<SafeCallError> for GovernanceError {
+    fn from(error: SafeCallError) -> Self {
+        match error {
+            SafeCallError::Contract(_) => GovernanceError::VotingTokenError,
+            SafeCallError::Auth => GovernanceError::VotingTokenAuth,
+            SafeCallError::NotFound => GovernanceError::VotingTokenNotFound,
+            SafeCallError::Budget => GovernanceError::VotingTokenBudget,
+            SafeCallError::Decode => GovernanceError::VotingTokenDecode,
+            SafeCallError::Aborted => GovernanceError::VotingTokenAborted,
+            SafeCallError::Untrusted => GovernanceError::VotingTokenUntrusted,
+        }
     }
 }
 
-impl From<soroban_sdk::Error> for GovernanceError {
-    fn from(_error: soroban_sdk::Error) -> Self {
-        GovernanceError::Unauthorized
-    }
-}
- 
 pub struct GovernanceManager;
 
 impl GovernanceManager {
     /// Validate that an address has a specific role
-    pub fn require_role(env: &Env, address: &Address, required_role: GovernanceRole) {
-        let roles_key = symbol_short!("roles");
-        let role_map: soroban_sdk::Map<Address, GovernanceRole> = env
-            .storage()
-            .persistent()
-            .get(&roles_key)
-            .unwrap_or_else(|| soroban_sdk::Map::new(env));
-
-        let user_role = role_map.get(address.clone()).unwrap_or(GovernanceRole::Executor);
-        
-        if user_role > required_role {
-            panic!("UNAUTH");
-        }
+    pub fn require_role(
+        env: &Env,
+        address: &Address,
+        required_role: GovernanceRole,
+    ) -> Result<(), GovernanceError> {
+        RoleManager::require_role(env, address, &required_role)
     }
 
     /// Create a new upgrade proposal
@@ -92,60 +388,193 @@ impl GovernanceManager {
         new_contract_hash: Symbol,
         target_contract: Address,
         description: Symbol,
-        approval_threshold: u32,
-        approvers: Vec<Address>,
-        timelock_delay: u64,
     ) -> Result<u64, GovernanceError> {
-        // Validate proposer is admin
-        Self::require_role(env, &proposer, GovernanceRole::Admin);
+        let id = Self::propose(
+            env,
+            proposer,
+            ProposalAction::Upgrade(new_contract_hash),
+            description,
+        )?;
+
+        // Upgrades may name a contract other than the one hosting governance
+        let mut proposal = Self::get_proposal(env, id)?;
+        proposal.target_contract = target_contract;
+        Self::store_proposal(env, &proposal);
+
+        Ok(id)
+    }
+
+    /// Create a new proposal carrying a typed action for this contract, decided by the council
+    pub fn propose(
+        env: &Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        let actions = soroban_sdk::vec![env, action];
+        Self::propose_batch(env, proposer, actions, description)
+    }
 
-        // Validate threshold
-        if approval_threshold == 0 || approval_threshold > approvers.len() as u32 {
-            return Err(GovernanceError::InvalidThreshold);
+    /// Create a proposal whose actions run in order and atomically, decided by the council.
+    ///
+    /// The timelock is the longest one configured for the classes of its actions.
+    pub fn propose_batch(
+        env: &Env,
+        proposer: Address,
+        actions: Vec<ProposalAction>,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        if actions.is_empty() || actions.len() > MAX_PROPOSAL_ACTIONS {
+            return Err(GovernanceError::InvalidAction);
         }
 
-        // Get next proposal ID
-        let proposal_counter_key = symbol_short!("prop_cnt");
-        let proposal_id: u64 = env
-            .storage()
-            .persistent()
-            .get(&proposal_counter_key)
-            .unwrap_or(0u64);
+        // Role changes are proposed by the role's admin; everything else by Admin
+        for action in actions.iter() {
+            let required_role = match &action {
+                ProposalAction::GrantRole(_, role) | ProposalAction::RevokeRole(_, role) => {
+                    RoleManager::role_admin(env, role)
+                }
+                _ => GovernanceRole::Admin,
+            };
+            Self::require_role(env, &proposer, required_role)?;
+            Self::check_action(env, &action)?;
+        }
 
-        let next_id = proposal_id + 1;
+        let timelock = Self::timelock_for(env, &actions);
+        Self::create_council_proposal(env, proposer, actions, description, timelock)
+    }
+
+    /// Propose rolling back the last upgrade (Admin), under the short `ROLLBACK_TIMELOCK`.
+    ///
+    /// Refused up front, and again at execution, if there is no earlier hash or
+    /// the storage schema is ahead of what the earlier code expects.
+    pub fn propose_rollback(
+        env: &Env,
+        proposer: Address,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        Self::require_role(env, &proposer, GovernanceRole::Admin)?;
+        UpgradeManager::check_rollback(env)?;
+
+        let actions = soroban_sdk::vec![env, ProposalAction::Rollback];
+        let timelock = ROLLBACK_TIMELOCK.max(Self::min_timelock(env));
+        Self::create_council_proposal(env, proposer, actions, description, timelock)
+    }
+
+    /// Store a new council proposal for already-validated actions
+    fn create_council_proposal(
+        env: &Env,
+        proposer: Address,
+        actions: Vec<ProposalAction>,
+        description: Symbol,
+        timelock_delay: u64,
+    ) -> Result<u64, GovernanceError> {
+        // The current council decides; fails if it was never set up
+        let council = CouncilManager::get(env);
+        CouncilManager::validate(&council)?;
+
+        let next_id = Self::next_proposal_id(env);
 
         let proposal = UpgradeProposal {
             id: next_id,
             proposer,
-            new_contract_hash,
-            target_contract,
+            actions,
+            target_contract: env.current_contract_address(),
             description,
-            approval_threshold,
-            approvers,
+            approval_threshold: council.threshold,
+            approvers: council.members,
             approvals_count: 0,
+            veto_threshold: council.veto_threshold,
+            rejections_count: 0,
             status: ProposalStatus::Pending,
             created_at: env.ledger().timestamp(),
-            execution_time: env.ledger().timestamp() + timelock_delay,
+            execution_time: 0,
+            timelock: timelock_delay,
+            voting_deadline: env.ledger().timestamp() + VOTING_PERIOD,
+            execution_window: EXECUTION_WINDOW,
             executed: false,
+            outcomes: Vec::new(env),
+            mode: VotingMode::Council,
+            snapshot_ledger: env.ledger().sequence(),
+            emergency: false,
         };
 
         // Store proposal
-        let proposals_key = symbol_short!("props");
-        let mut proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
-            .storage()
-            .persistent()
-            .get(&proposals_key)
-            .unwrap_or_else(|| soroban_sdk::Map::new(env));
+        Self::store_proposal(env, &proposal);
 
-        proposals.set(next_id, proposal);
-        env.storage().persistent().set(&proposals_key, &proposals);
+        Ok(next_id)
+    }
 
-        // Update counter
-        env.storage()
-            .persistent()
-            .set(&proposal_counter_key, &next_id);
+    /// Create an emergency proposal (Admin or Guardian).
+    ///
+    /// Limited to pauses and guardian rotation; needs every council member's
+    /// approval but has no timelock, and only a 1-day voting period.
+    pub fn propose_emergency(
+        env: &Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        if !RoleManager::has_role(env, &proposer, &GovernanceRole::Admin)
+            && !RoleManager::has_role(env, &proposer, &GovernanceRole::Guardian)
+        {
+            return Err(GovernanceError::Unauthorized);
+        }
 
-        Ok(next_id)
+        if !Self::is_emergency_action(env, &action) {
+            return Err(GovernanceError::InvalidAction);
+        }
+        Self::check_action(env, &action)?;
+
+        let council = CouncilManager::get(env);
+        CouncilManager::validate(&council)?;
+
+        let now = env.ledger().timestamp();
+        let proposal = UpgradeProposal {
+            id: Self::next_proposal_id(env),
+            proposer: proposer.clone(),
+            actions: soroban_sdk::vec![env, action],
+            target_contract: env.current_contract_address(),
+            description,
+            approval_threshold: council.members.len(),
+            approvers: council.members,
+            approvals_count: 0,
+            veto_threshold: council.veto_threshold,
+            rejections_count: 0,
+            status: ProposalStatus::Pending,
+            created_at: now,
+            execution_time: 0,
+            timelock: EMERGENCY_TIMELOCK,
+            voting_deadline: now + EMERGENCY_VOTING_PERIOD,
+            execution_window: EXECUTION_WINDOW,
+            executed: false,
+            outcomes: Vec::new(env),
+            mode: VotingMode::Council,
+            snapshot_ledger: env.ledger().sequence(),
+            emergency: true,
+        };
+
+        Self::store_proposal(env, &proposal);
+        Self::emit_emergency(env, &proposal, proposer, symbol_short!("propose"));
+
+        Ok(proposal.id)
+    }
+
+    /// Actions an emergency proposal may carry: pauses and their extensions (local, or a
+    /// `pause` call on a registered trusted target) and guardian rotation
+    pub fn is_emergency_action(env: &Env, action: &ProposalAction) -> bool {
+        match action {
+            ProposalAction::Call(target, function, _) => {
+                *function == EMERGENCY_CALL_SELECTOR && TargetRegistry::get(env, target).is_some()
+            }
+            _ => matches!(
+                action,
+                ProposalAction::Pause(_, _)
+                    | ProposalAction::ExtendPause(_, _)
+                    | ProposalAction::GrantRole(_, GovernanceRole::Guardian)
+                    | ProposalAction::RevokeRole(_, GovernanceRole::Guardian)
+            ),
+        }
     }
 
     /// Approve a proposal
@@ -154,56 +583,186 @@ impl GovernanceManager {
         proposal_id: u64,
         approver: Address,
     ) -> Result<(), GovernanceError> {
-        // Validate approver has permission
-        Self::require_role(env, &approver, GovernanceRole::Approver);
+        let mut proposal = Self::open_council_proposal(env, proposal_id)?;
+        Self::check_council_vote(env, &proposal, &approver)?;
 
-        let proposals_key = symbol_short!("props");
-        let mut proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
-            .storage()
-            .persistent()
-            .get(&proposals_key)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        Self::record_approval(env, &mut proposal, approver);
+        Self::store_proposal(env, &proposal);
 
-        let mut proposal = proposals
-            .get(proposal_id)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        Ok(())
+    }
 
-        // Validate proposal status
-        if proposal.status != ProposalStatus::Pending {
-            return Err(GovernanceError::InvalidProposal);
+    /// Record approvals from ed25519 signatures over `approval_digest` (anyone can relay).
+    ///
+    /// The batch is all or nothing: a duplicate signer, a non-member, a member
+    /// without a signing key, or a bad signature fails every approval in it.
+    /// Returns the number of approvals recorded.
+    pub fn approve_with_signatures(
+        env: &Env,
+        proposal_id: u64,
+        signatures: Vec<ApprovalSignature>,
+    ) -> Result<u32, GovernanceError> {
+        if signatures.is_empty() {
+            return Err(GovernanceError::InvalidAction);
         }
 
-        // Validate approver is in the list
-        if !proposal.approvers.iter().any(|a| a == approver) {
-            return Err(GovernanceError::Unauthorized);
+        let mut proposal = Self::open_council_proposal(env, proposal_id)?;
+        let digest: Bytes = Self::approval_digest(env, proposal_id).into();
+
+        // Check the whole batch before recording anything, signatures last
+        let mut signers: Vec<Address> = Vec::new(env);
+        let mut public_keys: Vec<BytesN<32>> = Vec::new(env);
+        for entry in signatures.iter() {
+            if signers.contains(&entry.approver) {
+                return Err(GovernanceError::DuplicateApproval);
+            }
+            Self::check_council_vote(env, &proposal, &entry.approver)?;
+
+            let public_key = Self::get_signing_key(env, &entry.approver)
+                .ok_or(GovernanceError::SigningKeyNotSet)?;
+            signers.push_back(entry.approver);
+            public_keys.push_back(public_key);
+        }
+        for (entry, public_key) in signatures.iter().zip(public_keys.iter()) {
+            // Traps on an invalid signature, which fails the whole call
+            env.crypto().ed25519_verify(&public_key, &digest, &entry.signature);
         }
 
-        // Check for duplicate approval
-        let approvals_key = symbol_short!("apprv");
-        let mut approvals: soroban_sdk::Map<(u64, Address), bool> = env
-            .storage()
+        for signer in signers.iter() {
+            Self::record_approval(env, &mut proposal, signer);
+        }
+        Self::store_proposal(env, &proposal);
+
+        Ok(signers.len())
+    }
+
+    /// Message council members sign to approve a proposal off-chain.
+    ///
+    /// sha256 over a domain tag, the network ID, this contract and the proposal
+    /// ID, so a signature can't be replayed on another network, contract or proposal.
+    pub fn approval_digest(env: &Env, proposal_id: u64) -> BytesN<32> {
+        let preimage = (
+            symbol_short!("gov_appr"),
+            env.ledger().network_id(),
+            env.current_contract_address(),
+            proposal_id,
+        );
+        env.crypto().sha256(&preimage.to_xdr(env))
+    }
+
+    /// Register the ed25519 key a council member signs approvals with
+    pub fn set_signing_key(
+        env: &Env,
+        approver: &Address,
+        public_key: &BytesN<32>,
+    ) -> Result<(), GovernanceError> {
+        Self::require_role(env, approver, GovernanceRole::Approver)?;
+
+        env.storage()
             .persistent()
-            .get(&approvals_key)
-            .unwrap_or_else(|| soroban_sdk::Map::new(env));
+            .set(&(symbol_short!("sigkey"), approver.clone()), public_key);
+        Ok(())
+    }
 
-        if approvals.get((proposal_id, approver.clone())).is_some() {
-            return Err(GovernanceError::DuplicateApproval);
+    /// Get a council member's registered signing key
+    pub fn get_signing_key(env: &Env, approver: &Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("sigkey"), approver.clone()))
+    }
+
+    /// Hand an approver's vote to `delegate` until `until` (at most `MAX_DELEGATION_PERIOD` away).
+    ///
+    /// Replaces any earlier delegation from the same approver.
+    pub fn delegate_approval(
+        env: &Env,
+        delegator: Address,
+        delegate: Address,
+        until: u64,
+    ) -> Result<(), GovernanceError> {
+        Self::require_role(env, &delegator, GovernanceRole::Approver)?;
+
+        let now = env.ledger().timestamp();
+        if delegate == delegator || until <= now || until > now + MAX_DELEGATION_PERIOD {
+            return Err(GovernanceError::InvalidAction);
         }
 
-        // Record approval
-        approvals.set((proposal_id, approver), true);
-        env.storage().persistent().set(&approvals_key, &approvals);
+        let delegation = ApprovalDelegation {
+            delegate: delegate.clone(),
+            until,
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("deleg"), delegator.clone()), &delegation);
+
+        let event = DelegationEvent {
+            delegator,
+            delegate,
+            until,
+            timestamp: now,
+        };
+        env.events().publish((symbol_short!("delegate"),), event);
 
-        // Increment approval count
-        proposal.approvals_count += 1;
+        Ok(())
+    }
 
-        // Check if threshold reached
-        if proposal.approvals_count >= proposal.approval_threshold {
-            proposal.status = ProposalStatus::Approved;
+    /// End an approver's delegation before it lapses
+    pub fn revoke_delegation(env: &Env, delegator: Address) -> Result<(), GovernanceError> {
+        let delegation =
+            Self::get_delegation(env, &delegator).ok_or(GovernanceError::DelegationNotActive)?;
+
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("deleg"), delegator.clone()));
+
+        let event = DelegationEvent {
+            delegator,
+            delegate: delegation.delegate,
+            until: delegation.until,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("undeleg"),), event);
+
+        Ok(())
+    }
+
+    /// An approver's active delegation, if any (lapsed ones are ignored)
+    pub fn get_delegation(env: &Env, delegator: &Address) -> Option<ApprovalDelegation> {
+        let delegation: ApprovalDelegation = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("deleg"), delegator.clone()))?;
+        if env.ledger().timestamp() >= delegation.until {
+            return None;
+        }
+        Some(delegation)
+    }
+
+    /// Approve on behalf of `delegator`; counts once, for the delegator's seat
+    pub fn approve_as_delegate(
+        env: &Env,
+        proposal_id: u64,
+        delegate: Address,
+        delegator: Address,
+    ) -> Result<(), GovernanceError> {
+        match Self::get_delegation(env, &delegator) {
+            Some(delegation) if delegation.delegate == delegate => {}
+            _ => return Err(GovernanceError::DelegationNotActive),
         }
 
-        proposals.set(proposal_id, proposal);
-        env.storage().persistent().set(&proposals_key, &proposals);
+        let mut proposal = Self::open_council_proposal(env, proposal_id)?;
+        Self::check_council_vote(env, &proposal, &delegator)?;
+
+        Self::record_approval(env, &mut proposal, delegator.clone());
+        Self::store_proposal(env, &proposal);
+
+        let event = DelegatedApprovalEvent {
+            proposal_id,
+            delegate,
+            delegator,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("dlg_appr"),), event);
 
         Ok(())
     }
@@ -215,18 +774,13 @@ impl GovernanceManager {
         executor: Address,
     ) -> Result<(), GovernanceError> {
         // Validate executor has permission
-        Self::require_role(env, &executor, GovernanceRole::Executor);
+        Self::require_role(env, &executor, GovernanceRole::Executor)?;
 
-        let proposals_key = symbol_short!("props");
-        let mut proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
-            .storage()
-            .persistent()
-            .get(&proposals_key)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
 
-        let mut proposal = proposals
-            .get(proposal_id)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        if Self::is_expired(env, &proposal) {
+            return Err(GovernanceError::ProposalExpired);
+        }
 
         // Validate proposal is approved
         if proposal.status != ProposalStatus::Approved {
@@ -238,12 +792,42 @@ impl GovernanceManager {
             return Err(GovernanceError::TimelockNotExpired);
         }
 
+        // Run every action in order. Returning an error fails the invocation,
+        // which rolls back the actions that already ran.
+        let mut outcomes = Vec::new(env);
+        for (index, action) in proposal.actions.iter().enumerate() {
+            if let Err(code) = Self::run_action(env, &action, &executor) {
+                let event = ActionFailedEvent {
+                    proposal_id,
+                    index: index as u32,
+                    code,
+                    timestamp: env.ledger().timestamp(),
+                };
+                env.events().publish((symbol_short!("act_fail"),), event);
+                return Err(GovernanceError::ActionFailed);
+            }
+            outcomes.push_back(ActionOutcome::Succeeded);
+        }
+        proposal.outcomes = outcomes;
+
         // Mark as executed
         proposal.executed = true;
         proposal.status = ProposalStatus::Executed;
 
-        proposals.set(proposal_id, proposal);
-        env.storage().persistent().set(&proposals_key, &proposals);
+        if proposal.emergency {
+            Self::emit_emergency(env, &proposal, executor.clone(), symbol_short!("execute"));
+        }
+
+        let event = ProposalExecutedEvent {
+            proposal_id,
+            executor,
+            outcomes: proposal.outcomes.clone(),
+            executed_at: env.ledger().timestamp(),
+        };
+
+        Self::store_proposal(env, &proposal);
+
+        env.events().publish((symbol_short!("execute"),), event);
 
         Ok(())
     }
@@ -254,26 +838,128 @@ impl GovernanceManager {
         proposal_id: u64,
         rejector: Address,
     ) -> Result<(), GovernanceError> {
-        Self::require_role(env, &rejector, GovernanceRole::Approver);
+        let mut proposal = Self::open_council_proposal(env, proposal_id)?;
+        Self::check_council_vote(env, &proposal, &rejector)?;
 
-        let proposals_key = symbol_short!("props");
-        let mut proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
-            .storage()
+        env.storage()
             .persistent()
-            .get(&proposals_key)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+            .set(&(symbol_short!("apprv"), proposal_id, rejector.clone()), &CouncilVote::Reject);
+        if proposal.emergency {
+            Self::emit_emergency(env, &proposal, rejector, symbol_short!("reject"));
+        }
+
+        // Rejected once the veto threshold is reached
+        proposal.rejections_count += 1;
+        if proposal.rejections_count >= proposal.veto_threshold {
+            proposal.status = ProposalStatus::Rejected;
+        }
+
+        Self::store_proposal(env, &proposal);
+
+        Ok(())
+    }
+
+    /// Withdraw an approval before the proposal's timelock ends.
+    ///
+    /// An approved proposal that drops below its threshold goes back to `Pending`.
+    pub fn revoke_approval(
+        env: &Env,
+        proposal_id: u64,
+        approver: Address,
+    ) -> Result<(), GovernanceError> {
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
+
+        if Self::is_expired(env, &proposal) {
+            return Err(GovernanceError::ProposalExpired);
+        }
+
+        let open = match proposal.status {
+            ProposalStatus::Pending => true,
+            ProposalStatus::Approved => env.ledger().timestamp() < proposal.execution_time,
+            _ => false,
+        };
+        if !open || proposal.mode != VotingMode::Council {
+            return Err(GovernanceError::InvalidProposal);
+        }
+
+        let vote_key = (symbol_short!("apprv"), proposal_id, approver);
+        let vote: Option<CouncilVote> = env.storage().persistent().get(&vote_key);
+        if vote != Some(CouncilVote::Approve) {
+            return Err(GovernanceError::ApprovalNotFound);
+        }
+        env.storage().persistent().remove(&vote_key);
+
+        proposal.approvals_count -= 1;
+        if proposal.approvals_count < proposal.approval_threshold {
+            proposal.status = ProposalStatus::Pending;
+            proposal.execution_time = 0;
+        }
 
-        let mut proposal = proposals
-            .get(proposal_id)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        Self::store_proposal(env, &proposal);
 
-        if proposal.status != ProposalStatus::Pending {
+        Ok(())
+    }
+
+    /// Every recorded approve/reject vote on a council proposal, in council order
+    pub fn get_council_votes(
+        env: &Env,
+        proposal_id: u64,
+    ) -> Result<Vec<ApproverVote>, GovernanceError> {
+        let proposal = Self::load_proposal(env, proposal_id)?;
+
+        let mut votes = Vec::new(env);
+        for approver in proposal.approvers.iter() {
+            let vote: Option<CouncilVote> = env
+                .storage()
+                .persistent()
+                .get(&(symbol_short!("apprv"), proposal_id, approver.clone()));
+            if let Some(vote) = vote {
+                votes.push_back(ApproverVote { approver, vote });
+            }
+        }
+
+        Ok(votes)
+    }
+
+    /// Veto an approved proposal before its timelock ends (Guardian).
+    ///
+    /// Only one veto is allowed per `VETO_COOLDOWN`, and proposals made up only of
+    /// guardian rotations can't be vetoed, so a guardian can't block governance forever.
+    pub fn veto_proposal(
+        env: &Env,
+        proposal_id: u64,
+        guardian: Address,
+    ) -> Result<(), GovernanceError> {
+        Self::require_role(env, &guardian, GovernanceRole::Guardian)?;
+
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
+        let now = env.ledger().timestamp();
+
+        if proposal.status != ProposalStatus::Approved || now >= proposal.execution_time {
+            return Err(GovernanceError::InvalidProposal);
+        }
+        // Only a proposal that does nothing but rotate the guardian is exempt, so other
+        // actions can't hide from a veto by riding along with a rotation
+        if proposal.actions.iter().all(|action| Self::rotates_guardian(&action)) {
             return Err(GovernanceError::InvalidProposal);
         }
 
-        proposal.status = ProposalStatus::Rejected;
-        proposals.set(proposal_id, proposal);
-        env.storage().persistent().set(&proposals_key, &proposals);
+        let last_veto_key = symbol_short!("lastveto");
+        let last_veto: Option<u64> = env.storage().persistent().get(&last_veto_key);
+        if last_veto.is_some_and(|at| now < at + VETO_COOLDOWN) {
+            return Err(GovernanceError::VetoRateLimited);
+        }
+        env.storage().persistent().set(&last_veto_key, &now);
+
+        proposal.status = ProposalStatus::Vetoed;
+        Self::store_proposal(env, &proposal);
+
+        let event = ProposalVetoedEvent {
+            proposal_id,
+            guardian,
+            vetoed_at: now,
+        };
+        env.events().publish((symbol_short!("veto"),), event);
 
         Ok(())
     }
@@ -284,44 +970,498 @@ impl GovernanceManager {
         proposal_id: u64,
         admin: Address,
     ) -> Result<(), GovernanceError> {
-        Self::require_role(env, &admin, GovernanceRole::Admin);
+        Self::require_role(env, &admin, GovernanceRole::Admin)?;
 
-        let proposals_key = symbol_short!("props");
-        let mut proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
-            .storage()
-            .persistent()
-            .get(&proposals_key)
-            .ok_or(GovernanceError::ProposalNotFound)?;
-
-        let mut proposal = proposals
-            .get(proposal_id)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
 
         if proposal.executed {
             return Err(GovernanceError::InvalidProposal);
         }
 
         proposal.status = ProposalStatus::Cancelled;
-        proposals.set(proposal_id, proposal);
-        env.storage().persistent().set(&proposals_key, &proposals);
+        Self::store_proposal(env, &proposal);
 
         Ok(())
     }
 
-    /// Get a proposal by ID
+    /// Record that a proposal missed its voting deadline or execution window (anyone can call)
+    pub fn expire_proposal(env: &Env, proposal_id: u64) -> Result<(), GovernanceError> {
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
+
+        if !Self::is_expired(env, &proposal) {
+            return Err(GovernanceError::InvalidProposal);
+        }
+
+        let event = ProposalExpiredEvent {
+            proposal_id,
+            previous_status: proposal.status,
+            expired_at: env.ledger().timestamp(),
+        };
+
+        proposal.status = ProposalStatus::Expired;
+        Self::store_proposal(env, &proposal);
+
+        env.events().publish((symbol_short!("expire"),), event);
+
+        Ok(())
+    }
+
+    /// Get a proposal by ID, reporting `Expired` once its deadline has passed
     pub fn get_proposal(
         env: &Env,
         proposal_id: u64,
     ) -> Result<UpgradeProposal, GovernanceError> {
+        let mut proposal = Self::load_proposal(env, proposal_id)?;
+
+        if Self::is_expired(env, &proposal) {
+            proposal.status = ProposalStatus::Expired;
+        }
+
+        Ok(proposal)
+    }
+
+    /// List proposals with IDs after `cursor`, optionally filtered by effective status.
+    ///
+    /// Scans at most `MAX_PROPOSAL_SCAN` IDs per call, so a filtered page may come
+    /// back short with a `next_cursor` to resume from.
+    pub fn list_proposals(
+        env: &Env,
+        status: Option<ProposalStatus>,
+        cursor: u64,
+        limit: u32,
+    ) -> ProposalPage {
+        let mut proposals = Vec::new(env);
+        let last_id = Self::proposal_count(env);
+
+        let mut id = cursor;
+        let mut scanned = 0u32;
+        while id < last_id && proposals.len() < limit && scanned < MAX_PROPOSAL_SCAN {
+            id += 1;
+            scanned += 1;
+
+            if let Ok(proposal) = Self::get_proposal(env, id) {
+                if status.as_ref().is_none_or(|s| *s == proposal.status) {
+                    proposals.push_back(proposal);
+                }
+            }
+        }
+
+        ProposalPage {
+            proposals,
+            next_cursor: if id < last_id { Some(id) } else { None },
+        }
+    }
+
+    /// Move proposals and approvals out of the legacy `props`/`apprv` maps into
+    /// per-proposal keys (admin only). Returns the number of proposals moved.
+    ///
+    /// Legacy proposals are decoded as `LegacyUpgradeProposal` and become a
+    /// single `ProposalAction::Upgrade` of their `new_contract_hash`.
+    pub fn migrate_storage(env: &Env, admin: &Address) -> Result<u32, GovernanceError> {
+        Self::require_role(env, admin, GovernanceRole::Admin)?;
+
         let proposals_key = symbol_short!("props");
-        let proposals: soroban_sdk::Map<u64, UpgradeProposal> = env
+        let approvals_key = symbol_short!("apprv");
+
+        let proposals: soroban_sdk::Map<u64, LegacyUpgradeProposal> = env
             .storage()
             .persistent()
             .get(&proposals_key)
-            .ok_or(GovernanceError::ProposalNotFound)?;
+            .unwrap_or_else(|| soroban_sdk::Map::new(env));
+        let approvals: soroban_sdk::Map<(u64, Address), bool> = env
+            .storage()
+            .persistent()
+            .get(&approvals_key)
+            .unwrap_or_else(|| soroban_sdk::Map::new(env));
+
+        for (_, legacy) in proposals.iter() {
+            Self::store_proposal(env, &Self::upgrade_legacy_proposal(env, legacy));
+        }
+        for ((proposal_id, approver), _) in approvals.iter() {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("apprv"), proposal_id, approver), &CouncilVote::Approve);
+        }
+
+        env.storage().persistent().remove(&proposals_key);
+        env.storage().persistent().remove(&approvals_key);
+
+        Ok(proposals.len())
+    }
 
-        proposals
-            .get(proposal_id)
+    /// Number of proposals ever created (also the highest proposal ID)
+    pub fn proposal_count(env: &Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("prop_cnt"))
+            .unwrap_or(0u64)
+    }
+
+    /// Check whether a proposal has run out of time in its current status
+    pub fn is_expired(env: &Env, proposal: &UpgradeProposal) -> bool {
+        let now = env.ledger().timestamp();
+        match proposal.status {
+            // Token votes are closed out by finalize_vote instead
+            ProposalStatus::Pending => {
+                proposal.mode == VotingMode::Council && now > proposal.voting_deadline
+            }
+            ProposalStatus::Approved => now > proposal.execution_time + proposal.execution_window,
+            _ => false,
+        }
+    }
+
+    /// Get a governance-managed parameter
+    pub fn get_param(env: &Env, key: Symbol) -> Option<i128> {
+        ParamStore::get(env, &key)
+    }
+
+    /// Register the per-class timelock parameters at their defaults; existing keys are kept
+    pub fn define_timelocks(env: &Env) -> Result<(), GovernanceError> {
+        let defaults = [
+            (MIN_TIMELOCK_PARAM, DEFAULT_MIN_TIMELOCK),
+            (TIMELOCK_UPGRADE_PARAM, DEFAULT_UPGRADE_TIMELOCK),
+            (TIMELOCK_ROLES_PARAM, DEFAULT_ROLES_TIMELOCK),
+            (TIMELOCK_CONFIG_PARAM, DEFAULT_CONFIG_TIMELOCK),
+            (TIMELOCK_OPS_PARAM, DEFAULT_OPS_TIMELOCK),
+        ];
+        for (key, value) in defaults {
+            if ParamStore::spec(env, &key).is_some() {
+                continue;
+            }
+            // The floor itself can't be governed below its default
+            let min = if key == MIN_TIMELOCK_PARAM { DEFAULT_MIN_TIMELOCK } else { 0 };
+            let spec = ParamSpec {
+                min: min as i128,
+                max: MAX_TIMELOCK as i128,
+                change_delay: TIMELOCK_CHANGE_DELAY,
+            };
+            ParamStore::define(env, &key, &spec, value as i128)?;
+        }
+        Ok(())
+    }
+
+    /// Timelock for a set of actions: the longest configured for any of their classes,
+    /// and never below `min_timelock`
+    pub fn timelock_for(env: &Env, actions: &Vec<ProposalAction>) -> u64 {
+        actions
+            .iter()
+            .map(|action| Self::action_timelock(env, &action))
+            .fold(Self::min_timelock(env), u64::max)
+    }
+
+    /// Shortest timelock a non-emergency proposal can have (the `tl_min` parameter)
+    pub fn min_timelock(env: &Env) -> u64 {
+        ParamStore::get_u64(env, &MIN_TIMELOCK_PARAM).unwrap_or(DEFAULT_MIN_TIMELOCK)
+    }
+
+    /// Check that a role change keeps at least one admin and only revokes held roles
+    pub fn check_role_change(
+        env: &Env,
+        member: &Address,
+        role: &GovernanceRole,
+        grant: bool,
+    ) -> Result<(), GovernanceError> {
+        if grant {
+            return Ok(());
+        }
+
+        if !RoleManager::has_role(env, member, role) {
+            return Err(GovernanceError::RoleNotHeld);
+        }
+
+        if *role == GovernanceRole::Admin
+            && RoleManager::members(env, &GovernanceRole::Admin).len() <= 1
+        {
+            return Err(GovernanceError::LastAdmin);
+        }
+
+        Ok(())
+    }
+
+    /// Catch out-of-range parameter values and misplaced rollbacks before anyone votes on them
+    pub(crate) fn check_action(env: &Env, action: &ProposalAction) -> Result<(), GovernanceError> {
+        match action {
+            ProposalAction::Rollback => Err(GovernanceError::InvalidAction),
+            ProposalAction::SetParam(key, value) => ParamStore::check(env, key, *value).map(|_| ()),
+            ProposalAction::DefineParam(key, spec, value) => {
+                ParamStore::check_definition(env, key, spec, *value)
+            }
+            ProposalAction::Pause(_, duration) | ProposalAction::ExtendPause(_, duration) => {
+                if *duration == 0 || *duration > MAX_PAUSE_DURATION {
+                    return Err(GovernanceError::InvalidAction);
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reserve the next proposal ID
+    pub(crate) fn next_proposal_id(env: &Env) -> u64 {
+        let next_id = Self::proposal_count(env) + 1;
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("prop_cnt"), &next_id);
+
+        next_id
+    }
+
+    /// Read a proposal as stored, without the expiry overlay
+    pub(crate) fn load_proposal(
+        env: &Env,
+        proposal_id: u64,
+    ) -> Result<UpgradeProposal, GovernanceError> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("prop"), proposal_id))
             .ok_or(GovernanceError::ProposalNotFound)
     }
+
+    /// Write a proposal under its own key
+    pub(crate) fn store_proposal(env: &Env, proposal: &UpgradeProposal) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("prop"), proposal.id), proposal);
+    }
+
+    /// Carry a legacy proposal over as a council vote on a single `Upgrade` action
+    fn upgrade_legacy_proposal(env: &Env, legacy: LegacyUpgradeProposal) -> UpgradeProposal {
+        let mut outcomes = Vec::new(env);
+        if legacy.executed {
+            outcomes.push_back(ActionOutcome::Succeeded);
+        }
+
+        UpgradeProposal {
+            id: legacy.id,
+            proposer: legacy.proposer,
+            actions: soroban_sdk::vec![env, ProposalAction::Upgrade(legacy.new_contract_hash)],
+            target_contract: legacy.target_contract,
+            description: legacy.description,
+            approval_threshold: legacy.approval_threshold,
+            approvers: legacy.approvers,
+            approvals_count: legacy.approvals_count,
+            // Legacy proposals had no rejections; the approval threshold is the closest analogue
+            veto_threshold: legacy.approval_threshold,
+            rejections_count: 0,
+            status: legacy.status,
+            created_at: legacy.created_at,
+            execution_time: legacy.execution_time,
+            timelock: legacy.execution_time.saturating_sub(legacy.created_at),
+            voting_deadline: legacy.created_at + VOTING_PERIOD,
+            execution_window: EXECUTION_WINDOW,
+            executed: legacy.executed,
+            outcomes,
+            mode: VotingMode::Council,
+            snapshot_ledger: env.ledger().sequence(),
+            emergency: false,
+        }
+    }
+
+    /// Configured timelock for an action's class, or the class default if never defined
+    fn action_timelock(env: &Env, action: &ProposalAction) -> u64 {
+        let (key, default) = match action {
+            ProposalAction::Upgrade(_) | ProposalAction::Rollback => {
+                (TIMELOCK_UPGRADE_PARAM, DEFAULT_UPGRADE_TIMELOCK)
+            }
+            ProposalAction::GrantRole(_, _)
+            | ProposalAction::RevokeRole(_, _)
+            | ProposalAction::SetRoleAdmin(_, _)
+            | ProposalAction::SetCouncil(_) => (TIMELOCK_ROLES_PARAM, DEFAULT_ROLES_TIMELOCK),
+            ProposalAction::SetParam(_, _)
+            | ProposalAction::DefineParam(_, _, _)
+            | ProposalAction::SetVotingConfig(_)
+            | ProposalAction::TrustTarget(_)
+            | ProposalAction::UntrustTarget(_)
+            | ProposalAction::RequireTrustedTargets(_) => {
+                (TIMELOCK_CONFIG_PARAM, DEFAULT_CONFIG_TIMELOCK)
+            }
+            ProposalAction::Pause(_, _)
+            | ProposalAction::ExtendPause(_, _)
+            | ProposalAction::Unpause(_)
+            | ProposalAction::TransferTreasury(_, _, _)
+            | ProposalAction::Call(_, _, _) => (TIMELOCK_OPS_PARAM, DEFAULT_OPS_TIMELOCK),
+        };
+        ParamStore::get_u64(env, &key).unwrap_or(default)
+    }
+
+    /// Check whether an action changes who holds or administers the Guardian role
+    fn rotates_guardian(action: &ProposalAction) -> bool {
+        matches!(
+            action,
+            ProposalAction::GrantRole(_, GovernanceRole::Guardian)
+                | ProposalAction::RevokeRole(_, GovernanceRole::Guardian)
+                | ProposalAction::SetRoleAdmin(GovernanceRole::Guardian, _)
+        )
+    }
+
+    /// Load a council proposal that is still collecting votes
+    fn open_council_proposal(
+        env: &Env,
+        proposal_id: u64,
+    ) -> Result<UpgradeProposal, GovernanceError> {
+        let proposal = Self::load_proposal(env, proposal_id)?;
+
+        if Self::is_expired(env, &proposal) {
+            return Err(GovernanceError::ProposalExpired);
+        }
+        if proposal.status != ProposalStatus::Pending || proposal.mode != VotingMode::Council {
+            return Err(GovernanceError::InvalidProposal);
+        }
+
+        Ok(proposal)
+    }
+
+    /// Check that a council member may vote on a proposal and hasn't yet
+    fn check_council_vote(
+        env: &Env,
+        proposal: &UpgradeProposal,
+        member: &Address,
+    ) -> Result<(), GovernanceError> {
+        Self::require_role(env, member, GovernanceRole::Approver)?;
+
+        if !proposal.approvers.contains(member) {
+            return Err(GovernanceError::Unauthorized);
+        }
+
+        // One vote per approver, approve or reject
+        if env
+            .storage()
+            .persistent()
+            .has(&(symbol_short!("apprv"), proposal.id, member.clone()))
+        {
+            return Err(GovernanceError::DuplicateApproval);
+        }
+
+        Ok(())
+    }
+
+    /// Record an approval and move the proposal to `Approved` at its threshold.
+    ///
+    /// The timelock starts here, so a late approval still leaves the full
+    /// delay to veto or react.
+    fn record_approval(env: &Env, proposal: &mut UpgradeProposal, approver: Address) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("apprv"), proposal.id, approver.clone()), &CouncilVote::Approve);
+        if proposal.emergency {
+            Self::emit_emergency(env, proposal, approver, symbol_short!("approve"));
+        }
+
+        proposal.approvals_count += 1;
+        if proposal.approvals_count >= proposal.approval_threshold
+            && proposal.status != ProposalStatus::Approved
+        {
+            proposal.status = ProposalStatus::Approved;
+            proposal.execution_time = env.ledger().timestamp() + proposal.timelock;
+        }
+    }
+
+    fn emit_emergency(env: &Env, proposal: &UpgradeProposal, member: Address, action_type: Symbol) {
+        let event = EmergencyActionEvent {
+            action_id: proposal.id,
+            council_member: member,
+            action_type,
+            target_contract: proposal.target_contract.clone(),
+            description: proposal.description.clone(),
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("emergency"),), event);
+    }
+
+    /// Run a proposal action against this contract, returning an error code on failure
+    fn run_action(env: &Env, action: &ProposalAction, executor: &Address) -> Result<(), u32> {
+        match action {
+            // Record the hash being replaced so the upgrade can be rolled back
+            ProposalAction::Upgrade(new_hash) => {
+                UpgradeManager::record_upgrade(env, new_hash);
+                Ok(())
+            }
+            ProposalAction::Rollback => UpgradeManager::rollback(env).map(|_| ()).map_err(|e| e as u32),
+            ProposalAction::SetParam(key, value) => {
+                ParamStore::set(env, key, *value).map(|_| ()).map_err(|e| e as u32)
+            }
+            ProposalAction::DefineParam(key, spec, value) => {
+                ParamStore::define(env, key, spec, *value).map_err(|e| e as u32)
+            }
+            ProposalAction::GrantRole(member, role) => {
+                Self::check_role_change(env, member, role, true).map_err(|e| e as u32)?;
+                RoleManager::grant(env, member, role);
+                Ok(())
+            }
+            ProposalAction::RevokeRole(member, role) => {
+                Self::check_role_change(env, member, role, false).map_err(|e| e as u32)?;
+                RoleManager::revoke(env, member, role);
+                Ok(())
+            }
+            ProposalAction::SetRoleAdmin(role, admin_role) => {
+                RoleManager::set_role_admin(env, role, admin_role);
+                Ok(())
+            }
+            ProposalAction::Pause(scope, duration) => {
+                if *duration == 0 || *duration > MAX_PAUSE_DURATION {
+                    return Err(GovernanceError::InvalidAction as u32);
+                }
+                let now = env.ledger().timestamp();
+                let state = PauseState {
+                    paused_by: executor.clone(),
+                    paused_at: now,
+                    expires_at: now
+                        .checked_add(*duration)
+                        .ok_or(GovernanceError::InvalidAction as u32)?,
+                };
+                PauseManager::set(env, scope, &state);
+                Ok(())
+            }
+            ProposalAction::ExtendPause(scope, duration) => {
+                if *duration == 0 || *duration > MAX_PAUSE_DURATION {
+                    return Err(GovernanceError::InvalidAction as u32);
+                }
+                let mut state =
+                    PauseManager::get(env, scope).ok_or(GovernanceError::NotPaused as u32)?;
+                state.expires_at = state
+                    .expires_at
+                    .checked_add(*duration)
+                    .ok_or(GovernanceError::InvalidAction as u32)?;
+                PauseManager::set(env, scope, &state);
+                Ok(())
+            }
+            ProposalAction::Unpause(scope) => {
+                PauseManager::clear(env, scope);
+                Ok(())
+            }
+            ProposalAction::TransferTreasury(token, to, amount) => {
+                if *amount <= 0 {
+                    return Err(GovernanceError::InvalidAction as u32);
+                }
+                let client = token::Client::new(env, token);
+                match client.try_transfer(&env.current_contract_address(), to, amount) {
+                    Ok(Ok(())) => Ok(()),
+                    _ => Err(GovernanceError::TransferFailed as u32),
+                }
+            }
+            ProposalAction::Call(contract, func, args) => {
+                safe_invoke(env, contract, func, args.clone())
+                    .map(|_| ())
+                    .map_err(|e| e.code())
+            }
+            ProposalAction::SetVotingConfig(config) => {
+                TokenVoting::set_config(env, config).map_err(|e| e as u32)
+            }
+            ProposalAction::SetCouncil(council) => {
+                CouncilManager::set(env, council).map_err(|e| e as u32)
+            }
+            ProposalAction::TrustTarget(contract) => {
+                TargetRegistry::trust(env, contract);
+                Ok(())
+            }
+            ProposalAction::UntrustTarget(contract) => {
+                TargetRegistry::untrust(env, contract);
+                Ok(())
+            }
+            ProposalAction::RequireTrustedTargets(required) => {
+                TargetRegistry::set_required(env, *required);
+                Ok(())
+            }
+        }
+    }
 }
@@ -0,0 +1,61 @@
+use soroban_sdk::{contracterror, symbol_short, Env, Symbol};
+
+/// Reentrancy guard error codes (`errors::GUARD_ERRORS` block)
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GuardError {
+    /// The scope is already entered further up the call stack
+    Reentered = 7001,
+}
+
+/// Scoped reentrancy lock around code that calls out to untrusted contracts.
+///
+/// The flag lives in instance storage and is cleared when the guard is
+/// dropped, so `?` and early returns release it. A panic fails the whole
+/// invocation, which reverts the flag along with every other write.
+///
+/// The Soroban host also refuses a contract that calls back into itself
+/// while on the call stack; the guard keeps these paths safe regardless of
+/// host behavior and gives callers a typed error.
+pub struct ReentrancyGuard {
+    env: Env,
+    scope: Symbol,
+}
+
+impl ReentrancyGuard {
+    /// Take the lock for `scope`, failing if it is already held
+    pub fn enter(env: &Env, scope: Symbol) -> Result<Self, GuardError> {
+        let key = (symbol_short!("lock"), scope.clone());
+        if env.storage().instance().has(&key) {
+            return Err(GuardError::Reentered);
+        }
+        env.storage().instance().set(&key, &true);
+
+        Ok(Self { env: env.clone(), scope })
+    }
+
+    /// Whether the lock for `scope` is currently held
+    pub fn is_entered(env: &Env, scope: Symbol) -> bool {
+        env.storage().instance().has(&(symbol_short!("lock"), scope))
+    }
+}
+
+impl Drop for ReentrancyGuard {
+    fn drop(&mut self) {
+        self.env
+            .storage()
+            .instance()
+            .remove(&(symbol_short!("lock"), self.scope.clone()));
+    }
+}
+
+/// Run `f` while holding the lock for `scope`
+pub fn non_reentrant<T, E: From<GuardError>>(
+    env: &Env,
+    scope: Symbol,
+    f: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let _guard = ReentrancyGuard::enter(env, scope)?;
+    f()
+}
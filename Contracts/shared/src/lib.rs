@@ -1,6 +1,6 @@
 //! Shared utilities and types for Stellara contracts
 
-use soroban_sdk::contracttype;
+use soroban_sdk::{contracttype, String};
 
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -10,14 +10,15 @@ pub struct ContractConfig {
     pub is_paused: bool,
 }
 
+pub mod council;
+pub mod errors;
 pub mod fees;
 pub mod governance;
-
-/// Standard contract error codes
-pub mod errors {
-    pub const UNAUTHORIZED: &str = "UNAUTHORIZED";
-    pub const NOT_FOUND: &str = "NOT_FOUND";
-    pub const INVALID_AMOUNT: &str = "INVALID_AMOUNT";
-    pub const PAUSED: &str = "PAUSED";
-    pub const ALREADY_EXISTS: &str = "ALREADY_EXISTS";
-}
+pub mod guard;
+pub mod params;
+pub mod pause;
+pub mod ratelimit;
+pub mod roles;
+pub mod safe_call;
+pub mod upgrade;
+pub mod voting;
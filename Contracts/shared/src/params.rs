@@ -0,0 +1,172 @@
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol, Vec};
+
+use crate::governance::GovernanceError;
+
+/// Bounds and change delay for a governance-managed parameter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamSpec {
+    pub min: i128,
+    pub max: i128,
+    pub change_delay: u64,            // Seconds before a new value takes effect
+}
+
+/// One recorded change to a parameter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamChange {
+    pub old_value: i128,
+    pub new_value: i128,
+    pub scheduled_at: u64,
+    pub effective_at: u64,
+}
+
+/// Parameter change event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ParamChangedEvent {
+    pub key: Symbol,
+    pub old_value: i128,
+    pub new_value: i128,
+    pub effective_at: u64,
+}
+
+/// Bounded key/value store for protocol parameters.
+///
+/// Values only change through `set`, which callers gate behind governance;
+/// a new value takes effect once its key's change delay has passed.
+pub struct ParamStore;
+
+impl ParamStore {
+    /// Register a new parameter with its bounds and starting value
+    pub fn define(
+        env: &Env,
+        key: &Symbol,
+        spec: &ParamSpec,
+        value: i128,
+    ) -> Result<(), GovernanceError> {
+        Self::check_definition(env, key, spec, value)?;
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("pspec"), key.clone()), spec);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("param"), key.clone()), &value);
+        Ok(())
+    }
+
+    /// Check that a parameter could be defined: unknown key, sane bounds, value inside them
+    pub fn check_definition(
+        env: &Env,
+        key: &Symbol,
+        spec: &ParamSpec,
+        value: i128,
+    ) -> Result<(), GovernanceError> {
+        if Self::spec(env, key).is_some() {
+            return Err(GovernanceError::InvalidAction);
+        }
+        if spec.min > spec.max || value < spec.min || value > spec.max {
+            return Err(GovernanceError::ParamOutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Bounds and change delay of a parameter
+    pub fn spec(env: &Env, key: &Symbol) -> Option<ParamSpec> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("pspec"), key.clone()))
+    }
+
+    /// Check that `value` is an allowed new value for a defined parameter
+    pub fn check(env: &Env, key: &Symbol, value: i128) -> Result<ParamSpec, GovernanceError> {
+        let spec = Self::spec(env, key).ok_or(GovernanceError::UnknownParam)?;
+        if value < spec.min || value > spec.max {
+            return Err(GovernanceError::ParamOutOfBounds);
+        }
+        Ok(spec)
+    }
+
+    /// Schedule a new value; returns when it takes effect.
+    ///
+    /// Fails while an earlier change to the same key is still waiting out its delay.
+    pub fn set(env: &Env, key: &Symbol, value: i128) -> Result<u64, GovernanceError> {
+        let spec = Self::check(env, key, value)?;
+        let now = env.ledger().timestamp();
+
+        let pending_key = (symbol_short!("ppend"), key.clone());
+        if let Some(pending) = env.storage().persistent().get::<_, ParamChange>(&pending_key) {
+            if now < pending.effective_at {
+                return Err(GovernanceError::ParamChangePending);
+            }
+        }
+
+        let change = ParamChange {
+            old_value: Self::get(env, key).unwrap_or(value),
+            new_value: value,
+            scheduled_at: now,
+            effective_at: now + spec.change_delay,
+        };
+
+        // Fold the value in now so reads don't depend on the pending entry
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("param"), key.clone()), &change.old_value);
+        env.storage().persistent().set(&pending_key, &change);
+
+        let history_key = (symbol_short!("phist"), key.clone());
+        let mut history: Vec<ParamChange> = env
+            .storage()
+            .persistent()
+            .get(&history_key)
+            .unwrap_or(Vec::new(env));
+        history.push_back(change.clone());
+        env.storage().persistent().set(&history_key, &history);
+
+        let event = ParamChangedEvent {
+            key: key.clone(),
+            old_value: change.old_value,
+            new_value: value,
+            effective_at: change.effective_at,
+        };
+        env.events().publish((symbol_short!("param"),), event);
+
+        Ok(change.effective_at)
+    }
+
+    /// Current value of a parameter, including a scheduled change whose delay has passed
+    pub fn get(env: &Env, key: &Symbol) -> Option<i128> {
+        let pending: Option<ParamChange> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("ppend"), key.clone()));
+        if let Some(change) = pending {
+            if env.ledger().timestamp() >= change.effective_at {
+                return Some(change.new_value);
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("param"), key.clone()))
+    }
+
+    /// Current value as a `u64`, e.g. for durations
+    pub fn get_u64(env: &Env, key: &Symbol) -> Option<u64> {
+        Self::get(env, key).and_then(|value| u64::try_from(value).ok())
+    }
+
+    /// Current value as a `u32`, e.g. for counts and basis points
+    pub fn get_u32(env: &Env, key: &Symbol) -> Option<u32> {
+        Self::get(env, key).and_then(|value| u32::try_from(value).ok())
+    }
+
+    /// Every change scheduled for a parameter, oldest first
+    pub fn history(env: &Env, key: &Symbol) -> Vec<ParamChange> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("phist"), key.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+}
@@ -0,0 +1,94 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+/// Longest a single pause (or extension) may last: 3 days
+pub const MAX_PAUSE_DURATION: u64 = 259_200;
+
+/// How long a scope stays open after a pause lapses before it can be paused directly
+/// again, so back-to-back pauses can't keep it shut for more than half the time
+pub const PAUSE_COOLDOWN: u64 = MAX_PAUSE_DURATION;
+
+/// What a pause applies to
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PauseScope {
+    /// Every pausable entry point
+    Global,
+    /// Trading on a single pair
+    Pair(Symbol),
+    /// A single entry point, by function name
+    Function(Symbol),
+}
+
+/// An active pause
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseState {
+    pub paused_by: Address,
+    pub paused_at: u64,
+    pub expires_at: u64,
+}
+
+/// Pause/unpause event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PauseEvent {
+    pub scope: PauseScope,
+    pub caller: Address,
+    pub expires_at: u64,
+    pub timestamp: u64,
+}
+
+pub struct PauseManager;
+
+impl PauseManager {
+    /// Get the pause for a scope, ignoring pauses that have expired
+    pub fn get(env: &Env, scope: &PauseScope) -> Option<PauseState> {
+        let state = Self::last(env, scope)?;
+
+        if env.ledger().timestamp() >= state.expires_at {
+            return None;
+        }
+
+        Some(state)
+    }
+
+    /// Get the last pause stored for a scope, even if it has expired
+    pub fn last(env: &Env, scope: &PauseScope) -> Option<PauseState> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("pause"), scope.clone()))
+    }
+
+    /// Check whether a scope is currently paused
+    pub fn is_paused(env: &Env, scope: &PauseScope) -> bool {
+        Self::get(env, scope).is_some()
+    }
+
+    /// Check whether a call to `function` on `pair` is blocked by any pause
+    pub fn is_blocked(env: &Env, function: Symbol, pair: Option<Symbol>) -> bool {
+        if Self::is_paused(env, &PauseScope::Global)
+            || Self::is_paused(env, &PauseScope::Function(function))
+        {
+            return true;
+        }
+
+        match pair {
+            Some(pair) => Self::is_paused(env, &PauseScope::Pair(pair)),
+            None => false,
+        }
+    }
+
+    /// Store a pause for a scope
+    pub fn set(env: &Env, scope: &PauseScope, state: &PauseState) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("pause"), scope.clone()), state);
+    }
+
+    /// Clear the pause for a scope
+    pub fn clear(env: &Env, scope: &PauseScope) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("pause"), scope.clone()));
+    }
+}
@@ -0,0 +1,114 @@
+use soroban_sdk::{contracterror, contracttype, symbol_short, Address, Env, Symbol};
+
+/// Assumed ledger close time, used to turn a window into a storage TTL
+pub const LEDGER_CLOSE_SECONDS: u64 = 5;
+
+/// Longest window a limit may use: 90 days, well inside the network's maximum entry TTL
+pub const MAX_WINDOW: u64 = 7_776_000;
+
+/// Rate limit error codes (`errors::RATE_LIMIT_ERRORS` block)
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RateLimitError {
+    /// Not enough allowance left in the current window
+    RateLimited = 8001,
+    /// Window longer than `MAX_WINDOW`
+    InvalidWindow = 8002,
+}
+
+/// Token bucket: up to `capacity` units, refilled evenly over `window` seconds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    pub capacity: u64,
+    pub window: u64,
+}
+
+impl RateLimit {
+    /// Check the window fits in `MAX_WINDOW`
+    pub fn check(&self) -> Result<(), RateLimitError> {
+        if self.window > MAX_WINDOW {
+            return Err(RateLimitError::InvalidWindow);
+        }
+        Ok(())
+    }
+}
+
+/// Bucket state per (scope, address); a missing bucket is full
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Bucket {
+    tokens: u64,
+    updated_at: u64,
+}
+
+/// "N units per window per address" limiter.
+///
+/// Buckets live in temporary storage with a TTL covering the window; an
+/// expired bucket would have refilled completely anyway.
+pub struct RateLimiter;
+
+impl RateLimiter {
+    /// Take `cost` units from the address's bucket for `scope`; returns what is left
+    pub fn consume(
+        env: &Env,
+        scope: &Symbol,
+        address: &Address,
+        limit: &RateLimit,
+        cost: u64,
+    ) -> Result<u64, RateLimitError> {
+        let mut bucket = Self::refilled(env, scope, address, limit);
+        if cost > bucket.tokens {
+            return Err(RateLimitError::RateLimited);
+        }
+        bucket.tokens -= cost;
+
+        let key = Self::key(scope, address);
+        // Never ask for more than the network allows, or every call would abort.
+        // `max_ttl` counts the current ledger; `extend_ttl` counts from after it.
+        let ttl = u32::try_from(limit.window.div_ceil(LEDGER_CLOSE_SECONDS) + 1)
+            .unwrap_or(u32::MAX)
+            .min(env.storage().max_ttl().saturating_sub(1));
+        env.storage().temporary().set(&key, &bucket);
+        env.storage().temporary().extend_ttl(&key, ttl, ttl);
+
+        Ok(bucket.tokens)
+    }
+
+    /// Units the address can spend right now
+    pub fn remaining(env: &Env, scope: &Symbol, address: &Address, limit: &RateLimit) -> u64 {
+        Self::refilled(env, scope, address, limit).tokens
+    }
+
+    fn refilled(env: &Env, scope: &Symbol, address: &Address, limit: &RateLimit) -> Bucket {
+        let now = env.ledger().timestamp();
+        let full = Bucket { tokens: limit.capacity, updated_at: now };
+        if limit.window == 0 {
+            return full;
+        }
+
+        let bucket: Bucket = match env.storage().temporary().get(&Self::key(scope, address)) {
+            Some(bucket) => bucket,
+            None => return full,
+        };
+
+        let elapsed = now.saturating_sub(bucket.updated_at) as u128;
+        let refill = elapsed * limit.capacity as u128 / limit.window as u128;
+        let tokens = bucket.tokens as u128 + refill;
+        if tokens >= limit.capacity as u128 {
+            return full;
+        }
+
+        // Only advance the clock by the time actually turned into tokens,
+        // so partial refills aren't lost to rounding
+        Bucket {
+            tokens: tokens as u64,
+            updated_at: bucket.updated_at + (refill * limit.window as u128 / limit.capacity as u128) as u64,
+        }
+    }
+
+    fn key(scope: &Symbol, address: &Address) -> (Symbol, Symbol, Address) {
+        (symbol_short!("rate"), scope.clone(), address.clone())
+    }
+}
@@ -0,0 +1,100 @@
+use soroban_sdk::{symbol_short, Address, Env, Vec};
+
+use crate::governance::{GovernanceError, GovernanceRole};
+
+/// Explicit role membership: a role bitset per address plus a member list per role
+pub struct RoleManager;
+
+impl RoleManager {
+    /// Bit used for a role in an address's role set
+    pub fn bit(role: &GovernanceRole) -> u32 {
+        1 << (*role as u32)
+    }
+
+    /// Role bitset held by an address (0 if it holds none)
+    pub fn roles_of(env: &Env, address: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("rolebits"), address.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Check whether an address holds a role
+    pub fn has_role(env: &Env, address: &Address, role: &GovernanceRole) -> bool {
+        Self::roles_of(env, address) & Self::bit(role) != 0
+    }
+
+    /// Fail with `Unauthorized` unless the address holds the role
+    pub fn require_role(
+        env: &Env,
+        address: &Address,
+        role: &GovernanceRole,
+    ) -> Result<(), GovernanceError> {
+        if Self::has_role(env, address, role) {
+            Ok(())
+        } else {
+            Err(GovernanceError::Unauthorized)
+        }
+    }
+
+    /// Every address holding a role
+    pub fn members(env: &Env, role: &GovernanceRole) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("rolemem"), *role))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Give an address a role (no-op if already held)
+    pub fn grant(env: &Env, address: &Address, role: &GovernanceRole) {
+        let bits = Self::roles_of(env, address);
+        if bits & Self::bit(role) != 0 {
+            return;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("rolebits"), address.clone()), &(bits | Self::bit(role)));
+
+        let mut members = Self::members(env, role);
+        members.push_back(address.clone());
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("rolemem"), *role), &members);
+    }
+
+    /// Take a role away from an address (no-op if not held)
+    pub fn revoke(env: &Env, address: &Address, role: &GovernanceRole) {
+        let bits = Self::roles_of(env, address);
+        if bits & Self::bit(role) == 0 {
+            return;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("rolebits"), address.clone()), &(bits & !Self::bit(role)));
+
+        let mut members = Self::members(env, role);
+        if let Some(index) = members.first_index_of(address.clone()) {
+            members.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("rolemem"), *role), &members);
+    }
+
+    /// Role whose holders may propose granting or revoking `role` (defaults to Admin)
+    pub fn role_admin(env: &Env, role: &GovernanceRole) -> GovernanceRole {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("roleadm"), *role))
+            .unwrap_or(GovernanceRole::Admin)
+    }
+
+    /// Change which role administers `role`
+    pub fn set_role_admin(env: &Env, role: &GovernanceRole, admin_role: &GovernanceRole) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("roleadm"), *role), admin_role);
+    }
+}
@@ -1,53 +1,206 @@
-use soroban_sdk::{Address, Env, Symbol, Val, Vec, Error};
+use soroban_sdk::{
+    contracttype, symbol_short,
+    xdr::{ScErrorCode, ScErrorType},
+    Address, Env, Error, InvokeError, Symbol, TryFromVal, Val, Vec,
+};
 
+/// Codes in the `errors::SAFE_CALL_ERRORS` block
 pub mod errors {
-    pub const CALL_FAILED: u32 = 2001;
-    pub const CONTRACT_NOT_FOUND: u32 = 2002;
+    pub const CALL_FAILED: u32 = 5001;
+    pub const CONTRACT_NOT_FOUND: u32 = 5002;
+    pub const AUTH_FAILED: u32 = 5003;
+    pub const BUDGET_EXCEEDED: u32 = 5004;
+    pub const DECODE_FAILED: u32 = 5005;
+    pub const UNTRUSTED_TARGET: u32 = 5006;
+}
+
+/// Why a cross-contract call failed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SafeCallError {
+    /// The callee returned a contract error with this code
+    Contract(u32),
+    /// The callee (or something it called) failed `require_auth`
+    Auth,
+    /// No contract at the address, or no such function on it
+    NotFound,
+    /// The call ran out of CPU or memory budget
+    Budget,
+    /// The call succeeded but its result isn't the expected type
+    Decode,
+    /// The callee trapped or panicked without a contract error
+    Aborted,
+    /// Trusted targets are required and this one isn't registered
+    Untrusted,
+}
+
+impl SafeCallError {
+    /// Error code for callers that report a plain `u32`; contract errors keep the callee's code
+    pub fn code(&self) -> u32 {
+        match self {
+            SafeCallError::Contract(code) => *code,
+            SafeCallError::Auth => errors::AUTH_FAILED,
+            SafeCallError::NotFound => errors::CONTRACT_NOT_FOUND,
+            SafeCallError::Budget => errors::BUDGET_EXCEEDED,
+            SafeCallError::Decode => errors::DECODE_FAILED,
+            SafeCallError::Aborted => errors::CALL_FAILED,
+            SafeCallError::Untrusted => errors::UNTRUSTED_TARGET,
+        }
+    }
+
+    fn from_invoke(error: Result<Error, InvokeError>) -> Self {
+        match error {
+            Ok(error) if error.is_type(ScErrorType::Contract) => {
+                SafeCallError::Contract(error.get_code())
+            }
+            Ok(error) if error.is_type(ScErrorType::Auth) => SafeCallError::Auth,
+            Ok(error) if error.is_type(ScErrorType::Budget) => SafeCallError::Budget,
+            Ok(error) if error.is_code(ScErrorCode::MissingValue) => SafeCallError::NotFound,
+            Ok(_) => SafeCallError::Aborted,
+            Err(InvokeError::Contract(code)) => SafeCallError::Contract(code),
+            Err(InvokeError::Abort) => SafeCallError::Aborted,
+        }
+    }
+}
+
+/// Failed cross-contract call event for debugging (topic `call_fail`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct CallFailedEvent {
+    pub contract: Address,
+    pub function: Symbol,
+    pub error: SafeCallError,
+    pub timestamp: u64,
+}
+
+/// A contract approved as a call target
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrustedTarget {
+    pub registered_at: u64,
+}
+
+/// Target registry change event for off-chain monitoring (topic `target`)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct TargetUpdatedEvent {
+    pub contract: Address,
+    pub trusted: bool,                    // false when removed
+    pub timestamp: u64,
+}
+
+/// Registry of trusted call targets; updated only through governance actions.
+///
+/// Entries are by address only. Contracts can't read another contract's code
+/// hash on-chain, so trusting an address also trusts whatever it upgrades to.
+pub struct TargetRegistry;
+
+impl TargetRegistry {
+    /// Register (or re-register) a trusted contract
+    pub fn trust(env: &Env, contract: &Address) {
+        let target = TrustedTarget {
+            registered_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("trusted"), contract.clone()), &target);
+        Self::emit(env, contract, true);
+    }
+
+    /// Remove a contract from the registry
+    pub fn untrust(env: &Env, contract: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("trusted"), contract.clone()));
+        Self::emit(env, contract, false);
+    }
+
+    /// Registry entry for a contract, if trusted
+    pub fn get(env: &Env, contract: &Address) -> Option<TrustedTarget> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("trusted"), contract.clone()))
+    }
+
+    /// Turn on (or off) refusing calls and fee tokens that aren't registered
+    pub fn set_required(env: &Env, required: bool) {
+        env.storage().persistent().set(&symbol_short!("tgt_req"), &required);
+    }
+
+    /// Whether call targets must be registered (off until governance turns it on)
+    pub fn is_required(env: &Env) -> bool {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("tgt_req"))
+            .unwrap_or(false)
+    }
+
+    fn emit(env: &Env, contract: &Address, trusted: bool) {
+        let event = TargetUpdatedEvent {
+            contract: contract.clone(),
+            trusted,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("target"),), event);
+    }
 }
 
 /// Safely invokes a contract method with error handling checks.
-/// 
+///
 /// # Arguments
 /// * `env` - The environment
 /// * `contract` - The address of the contract to call
 /// * `func` - The function name to call
 /// * `args` - The arguments to pass
-/// 
+///
 /// # Returns
-/// * `Result<Val, u32>` - The return value or an error code
+/// * `Result<Val, SafeCallError>` - The return value or why the call failed
 pub fn safe_invoke(
     env: &Env,
     contract: &Address,
     func: &Symbol,
     args: Vec<Val>,
-) -> Result<Val, u32> {
-    // 1. Defensive Check: Ensure contract exists/is deployed?
-    // Soroban doesn't have a direct "exists" check on Address easily accessible without trying to call 
-    // or checking ledger entries, but try_call handles non-existence as an error.
-
-    // 2. Try Call
-    // invoke_contract_try returns Result<Val, Error>
-    // We map generic errors to our specific codes if needed, or propagate.
-    
-    // Explicitly type the result to see what compiler thinks
-    let res: Result<Val, Error> = env.try_invoke_contract(contract, func, args);
-
-    match res {
-        Ok(val) => Ok(val),
-        Err(e) => {
-            // Log the error for debugging
-            // env.events().publish((Symbol::new(env, "call_failed"),), e);
-            
-            // In a real module we might inspect `e` to see if it's a missing contract vs logic error.
-            // For now, we wrap it.
-            Err(errors::CALL_FAILED)
+) -> Result<Val, SafeCallError> {
+    safe_invoke_typed(env, contract, func, args)
+}
+
+/// Like `safe_invoke`, but decodes the return value as `T`.
+///
+/// A value that doesn't decode as `T` fails with `SafeCallError::Decode`.
+/// Failures emit a `call_fail` event; it only persists if the caller
+/// handles the error instead of failing the transaction.
+pub fn safe_invoke_typed<T: TryFromVal<Env, Val>>(
+    env: &Env,
+    contract: &Address,
+    func: &Symbol,
+    args: Vec<Val>,
+) -> Result<T, SafeCallError> {
+    let error = if verify_target(env, contract) {
+        let res: Result<Result<T, T::Error>, Result<Error, InvokeError>> =
+            env.try_invoke_contract(contract, func, args);
+
+        match res {
+            Ok(Ok(val)) => return Ok(val),
+            Ok(Err(_)) => SafeCallError::Decode,
+            Err(error) => SafeCallError::from_invoke(error),
         }
-    }
+    } else {
+        SafeCallError::Untrusted
+    };
+
+    let event = CallFailedEvent {
+        contract: contract.clone(),
+        function: func.clone(),
+        error: error.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    env.events().publish((symbol_short!("call_fail"),), event);
+
+    Err(error)
 }
 
-/// Verifies a contract address is valid (basic check).
-pub fn verify_target(_env: &Env, _contract: &Address) -> bool {
-    // This is a placeholder. In Soroban, an Address is just a handle.
-    // We could check if it's a contract address vs account address if needed.
-    true
+/// Check that a contract may be called: it is registered in `TargetRegistry`,
+/// or the registry isn't being enforced
+pub fn verify_target(env: &Env, contract: &Address) -> bool {
+    !TargetRegistry::is_required(env) || TargetRegistry::get(env, contract).is_some()
 }
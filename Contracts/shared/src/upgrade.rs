@@ -0,0 +1,130 @@
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol, Vec};
+
+use crate::governance::GovernanceError;
+
+/// Timelock on approved rollback proposals: 1 hour
+pub const ROLLBACK_TIMELOCK: u64 = 3_600;
+
+/// Schema version of contracts that never recorded one
+pub const INITIAL_SCHEMA_VERSION: u32 = 1;
+
+/// Code hash an upgrade replaced
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PreviousHash {
+    None,                                  // First recorded upgrade; nothing to roll back to
+    Hash(Symbol),
+}
+
+/// One executed upgrade, kept so it can be rolled back
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeRecord {
+    pub previous_hash: PreviousHash,
+    pub new_hash: Symbol,
+    pub schema_version: u32,               // Storage schema when the upgrade ran
+    pub upgraded_at: u64,
+}
+
+/// Rollback event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RollbackEvent {
+    pub from_hash: Symbol,
+    pub to_hash: Symbol,
+    pub schema_version: u32,
+    pub rolled_back_at: u64,
+}
+
+pub struct UpgradeManager;
+
+impl UpgradeManager {
+    /// Code hash installed by the last executed upgrade or rollback
+    pub fn current_hash(env: &Env) -> Option<Symbol> {
+        env.storage().persistent().get(&symbol_short!("codehash"))
+    }
+
+    /// Current storage schema version
+    pub fn schema_version(env: &Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("schema"))
+            .unwrap_or(INITIAL_SCHEMA_VERSION)
+    }
+
+    /// Record a storage schema change; migrations call this after moving data
+    /// (forward, or back down before a rollback)
+    pub fn set_schema_version(env: &Env, version: u32) {
+        env.storage().persistent().set(&symbol_short!("schema"), &version);
+    }
+
+    /// Executed upgrades that can still be rolled back, oldest first
+    pub fn history(env: &Env) -> Vec<UpgradeRecord> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("upgrades"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Record an upgrade to `new_hash`, remembering the hash and schema it replaces
+    pub fn record_upgrade(env: &Env, new_hash: &Symbol) {
+        let record = UpgradeRecord {
+            previous_hash: match Self::current_hash(env) {
+                Some(hash) => PreviousHash::Hash(hash),
+                None => PreviousHash::None,
+            },
+            new_hash: new_hash.clone(),
+            schema_version: Self::schema_version(env),
+            upgraded_at: env.ledger().timestamp(),
+        };
+
+        let mut history = Self::history(env);
+        history.push_back(record);
+        env.storage().persistent().set(&symbol_short!("upgrades"), &history);
+        env.storage().persistent().set(&symbol_short!("codehash"), new_hash);
+    }
+
+    /// Check that the last upgrade can be rolled back and return it.
+    ///
+    /// Refused if there is no earlier hash, or if the schema moved past the
+    /// version the earlier code used and no down-migration brought it back.
+    pub fn check_rollback(env: &Env) -> Result<UpgradeRecord, GovernanceError> {
+        let record = Self::history(env)
+            .last()
+            .ok_or(GovernanceError::NoPreviousVersion)?;
+        if record.previous_hash == PreviousHash::None {
+            return Err(GovernanceError::NoPreviousVersion);
+        }
+        if Self::schema_version(env) > record.schema_version {
+            return Err(GovernanceError::SchemaAhead);
+        }
+        Ok(record)
+    }
+
+    /// Go back to the hash the last upgrade replaced
+    pub fn rollback(env: &Env) -> Result<Symbol, GovernanceError> {
+        let record = Self::check_rollback(env)?;
+        let previous_hash = match record.previous_hash {
+            PreviousHash::Hash(hash) => hash,
+            PreviousHash::None => return Err(GovernanceError::NoPreviousVersion),
+        };
+
+        // Drop the undone upgrade so a further rollback goes one step earlier
+        let mut history = Self::history(env);
+        history.pop_back();
+        env.storage().persistent().set(&symbol_short!("upgrades"), &history);
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("codehash"), &previous_hash);
+
+        let event = RollbackEvent {
+            from_hash: record.new_hash,
+            to_hash: previous_hash.clone(),
+            schema_version: Self::schema_version(env),
+            rolled_back_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("rollback"),), event);
+
+        Ok(previous_hash)
+    }
+}
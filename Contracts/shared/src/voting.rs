@@ -0,0 +1,337 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, IntoVal, Symbol, Vec};
+
+use crate::governance::{
+    GovernanceError, GovernanceManager, ProposalAction, ProposalStatus,
+    UpgradeProposal, VotingMode, EXECUTION_WINDOW,
+};
+use crate::safe_call::safe_invoke_typed;
+
+/// Token-weighted voting settings.
+///
+/// The token must expose `balance_at(account: Address, ledger: u32) -> i128`
+/// returning the checkpointed balance at the end of `ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotingConfig {
+    pub token: Address,
+    pub quorum: i128,                 // Minimum For + Against + Abstain voting power
+    pub pass_threshold_bps: u32,      // Share of For in For + Against needed to pass
+    pub voting_period: u64,           // Seconds a token vote stays open
+    pub proposal_threshold: i128,     // Voting power needed to open a token vote
+}
+
+/// Ballot choice
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum VoteChoice {
+    For = 0,
+    Against = 1,
+    Abstain = 2,
+}
+
+/// Running totals for a token vote
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteTally {
+    pub for_votes: i128,
+    pub against_votes: i128,
+    pub abstain_votes: i128,
+}
+
+/// A single voter's ballot
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteRecord {
+    pub choice: VoteChoice,
+    pub voting_power: i128,
+    pub cast_at: u64,
+}
+
+/// Token, quorum and pass threshold a token vote was opened under
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteTerms {
+    pub token: Address,
+    pub quorum: i128,
+    pub pass_threshold_bps: u32,
+}
+
+/// Vote event for off-chain indexing (`VoteCastEvent` in indexer/types.ts)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VoteCastEvent {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub vote_type: Symbol,            // "for", "against" or "abstain"
+    pub voting_power: i128,
+    pub reason: Symbol,
+    pub timestamp: u64,
+}
+
+/// Vote result event for off-chain indexing
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct VoteFinalizedEvent {
+    pub proposal_id: u64,
+    pub status: ProposalStatus,
+    pub tally: VoteTally,
+    pub finalized_at: u64,
+}
+
+pub struct TokenVoting;
+
+impl TokenVoting {
+    /// Current voting configuration
+    pub fn get_config(env: &Env) -> Option<VotingConfig> {
+        env.storage().persistent().get(&symbol_short!("vcfg"))
+    }
+
+    /// Validate and store the voting configuration
+    pub fn set_config(env: &Env, config: &VotingConfig) -> Result<(), GovernanceError> {
+        if config.quorum < 0
+            || config.proposal_threshold < 0
+            || config.pass_threshold_bps == 0
+            || config.pass_threshold_bps > 10_000
+            || config.voting_period == 0
+        {
+            return Err(GovernanceError::InvalidAction);
+        }
+
+        env.storage().persistent().set(&symbol_short!("vcfg"), config);
+        Ok(())
+    }
+
+    /// Open a token vote; its action's class timelock starts when it is finalized.
+    ///
+    /// The token, quorum and pass threshold are fixed here, so a later
+    /// `SetVotingConfig` doesn't move the bar for votes already open.
+    pub fn propose(
+        env: &Env,
+        proposer: Address,
+        action: ProposalAction,
+        description: Symbol,
+    ) -> Result<u64, GovernanceError> {
+        let config = Self::get_config(env).ok_or(GovernanceError::VotingNotConfigured)?;
+        GovernanceManager::check_action(env, &action)?;
+        let terms = VoteTerms {
+            token: config.token.clone(),
+            quorum: config.quorum,
+            pass_threshold_bps: config.pass_threshold_bps,
+        };
+
+        // Snapshot the previous ledger so balances can't be moved in to vote
+        let snapshot_ledger = env.ledger().sequence().saturating_sub(1);
+        if Self::voting_power(env, &terms.token, &proposer, snapshot_ledger)? < config.proposal_threshold {
+            return Err(GovernanceError::InsufficientVotingPower);
+        }
+
+        let actions = soroban_sdk::vec![env, action];
+        let timelock = GovernanceManager::timelock_for(env, &actions);
+
+        let now = env.ledger().timestamp();
+        let id = GovernanceManager::next_proposal_id(env);
+        let proposal = UpgradeProposal {
+            id,
+            proposer,
+            actions,
+            target_contract: env.current_contract_address(),
+            description,
+            approval_threshold: 0,
+            approvers: Vec::new(env),
+            approvals_count: 0,
+            veto_threshold: 0,
+            rejections_count: 0,
+            status: ProposalStatus::Pending,
+            created_at: now,
+            execution_time: 0,
+            timelock,
+            voting_deadline: now + config.voting_period,
+            execution_window: EXECUTION_WINDOW,
+            executed: false,
+            outcomes: Vec::new(env),
+            mode: VotingMode::TokenVote,
+            snapshot_ledger,
+            emergency: false,
+        };
+        GovernanceManager::store_proposal(env, &proposal);
+        env.storage().persistent().set(&(symbol_short!("vterms"), id), &terms);
+
+        Ok(id)
+    }
+
+    /// Cast a vote weighted by the voter's balance at the proposal snapshot
+    pub fn cast_vote(
+        env: &Env,
+        proposal_id: u64,
+        voter: Address,
+        choice: VoteChoice,
+        reason: Symbol,
+    ) -> Result<i128, GovernanceError> {
+        let proposal = Self::token_proposal(env, proposal_id)?;
+        if proposal.status != ProposalStatus::Pending
+            || env.ledger().timestamp() > proposal.voting_deadline
+        {
+            return Err(GovernanceError::VotingClosed);
+        }
+
+        let vote_key = (symbol_short!("vote"), proposal_id, voter.clone());
+        if env.storage().persistent().has(&vote_key) {
+            return Err(GovernanceError::AlreadyVoted);
+        }
+
+        let terms = Self::get_terms(env, proposal_id)?;
+        let voting_power = Self::voting_power(env, &terms.token, &voter, proposal.snapshot_ledger)?;
+        if voting_power <= 0 {
+            return Err(GovernanceError::InsufficientVotingPower);
+        }
+
+        let mut tally = Self::get_tally(env, proposal_id);
+        let vote_type = match choice {
+            VoteChoice::For => {
+                tally.for_votes += voting_power;
+                symbol_short!("for")
+            }
+            VoteChoice::Against => {
+                tally.against_votes += voting_power;
+                symbol_short!("against")
+            }
+            VoteChoice::Abstain => {
+                tally.abstain_votes += voting_power;
+                symbol_short!("abstain")
+            }
+        };
+
+        let now = env.ledger().timestamp();
+        let record = VoteRecord {
+            choice,
+            voting_power,
+            cast_at: now,
+        };
+        env.storage().persistent().set(&vote_key, &record);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("tally"), proposal_id), &tally);
+
+        let event = VoteCastEvent {
+            proposal_id,
+            voter,
+            vote_type,
+            voting_power,
+            reason,
+            timestamp: now,
+        };
+        env.events().publish((symbol_short!("vote"),), event);
+
+        Ok(voting_power)
+    }
+
+    /// Close a token vote after its deadline (anyone can call).
+    ///
+    /// Missing quorum expires the proposal; otherwise it is approved into the
+    /// timelock if For reaches the pass threshold, and rejected if not. Both
+    /// come from the terms snapshotted when the vote opened.
+    pub fn finalize(env: &Env, proposal_id: u64) -> Result<ProposalStatus, GovernanceError> {
+        let mut proposal = Self::token_proposal(env, proposal_id)?;
+        if proposal.status != ProposalStatus::Pending {
+            return Err(GovernanceError::InvalidProposal);
+        }
+        if env.ledger().timestamp() <= proposal.voting_deadline {
+            return Err(GovernanceError::VotingStillOpen);
+        }
+
+        let terms = Self::get_terms(env, proposal_id)?;
+        let tally = Self::get_tally(env, proposal_id);
+
+        let turnout = tally.for_votes + tally.against_votes + tally.abstain_votes;
+        let decisive = tally.for_votes + tally.against_votes;
+
+        proposal.status = if turnout < terms.quorum {
+            ProposalStatus::Expired
+        } else if decisive > 0
+            && tally.for_votes * 10_000 >= decisive * terms.pass_threshold_bps as i128
+        {
+            ProposalStatus::Approved
+        } else {
+            ProposalStatus::Rejected
+        };
+        // Like council approvals, the timelock runs from the moment the vote
+        // passes, however long after the deadline it is finalized
+        if proposal.status == ProposalStatus::Approved {
+            proposal.execution_time = env.ledger().timestamp() + proposal.timelock;
+        }
+
+        let status = proposal.status;
+        GovernanceManager::store_proposal(env, &proposal);
+
+        let event = VoteFinalizedEvent {
+            proposal_id,
+            status,
+            tally,
+            finalized_at: env.ledger().timestamp(),
+        };
+        env.events().publish((symbol_short!("vote_end"),), event);
+
+        Ok(status)
+    }
+
+    /// Running totals for a token vote
+    pub fn get_tally(env: &Env, proposal_id: u64) -> VoteTally {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("tally"), proposal_id))
+            .unwrap_or(VoteTally {
+                for_votes: 0,
+                against_votes: 0,
+                abstain_votes: 0,
+            })
+    }
+
+    /// Token, quorum and pass threshold a token vote is decided by.
+    ///
+    /// Votes opened before terms were snapshotted fall back to the current
+    /// configuration.
+    pub fn get_terms(env: &Env, proposal_id: u64) -> Result<VoteTerms, GovernanceError> {
+        if let Some(terms) = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("vterms"), proposal_id))
+        {
+            return Ok(terms);
+        }
+
+        let config = Self::get_config(env).ok_or(GovernanceError::VotingNotConfigured)?;
+        Ok(VoteTerms {
+            token: config.token,
+            quorum: config.quorum,
+            pass_threshold_bps: config.pass_threshold_bps,
+        })
+    }
+
+    /// A voter's ballot on a token vote
+    pub fn get_vote(env: &Env, proposal_id: u64, voter: Address) -> Option<VoteRecord> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("vote"), proposal_id, voter))
+    }
+
+    fn token_proposal(env: &Env, proposal_id: u64) -> Result<UpgradeProposal, GovernanceError> {
+        let proposal = GovernanceManager::get_proposal(env, proposal_id)?;
+        if proposal.mode != VotingMode::TokenVote {
+            return Err(GovernanceError::InvalidProposal);
+        }
+        Ok(proposal)
+    }
+
+    fn voting_power(
+        env: &Env,
+        token: &Address,
+        account: &Address,
+        ledger: u32,
+    ) -> Result<i128, GovernanceError> {
+        let args = soroban_sdk::vec![env, account.into_val(env), ledger.into_val(env)];
+        let func = Symbol::new(env, "balance_at");
+
+        Ok(safe_invoke_typed::<i128>(env, token, &func, args)?)
+    }
+}